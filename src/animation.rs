@@ -0,0 +1,210 @@
+use std::ops::{Add, Mul};
+
+use crate::math::vectors::Vector3;
+
+pub trait Lerp: Copy {
+    fn lerp(self, other: Self, t: f32) -> Self;
+}
+
+impl<T: Copy + Add<Output = T> + Mul<f32, Output = T>> Lerp for T {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self * (1.0 - t) + other * t
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    EaseInOut,
+    EaseOut,
+}
+
+impl Easing {
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug)]
+pub struct Tween<T: Lerp> {
+    pub from: T,
+    pub to: T,
+    pub duration: f32,
+    pub easing: Easing,
+}
+
+#[allow(dead_code)]
+impl<T: Lerp> Tween<T> {
+    pub fn new(from: T, to: T, duration: f32, easing: Easing) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            easing,
+        }
+    }
+
+    // Holds `from` before the start and `to` once the duration has elapsed.
+    pub fn sample(&self, elapsed: f32) -> T {
+        let t = if self.duration > 0.0 {
+            (elapsed / self.duration).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        self.from.lerp(self.to, self.easing.apply(t))
+    }
+}
+
+// Uniform Catmull-Rom spline through every control point. The whole path
+// maps to t in [0, 1], with point i reached at t = i / (len - 1); the end
+// points are repeated to give the first and last segments a neighbour.
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct CatmullRom {
+    points: Vec<Vector3<f32>>,
+}
+
+#[allow(dead_code)]
+impl CatmullRom {
+    pub fn new(points: Vec<Vector3<f32>>) -> Option<Self> {
+        if points.len() < 2 {
+            return None;
+        }
+
+        Some(Self { points })
+    }
+
+    pub fn sample(&self, t: f32) -> Vector3<f32> {
+        let ([p0, p1, p2, p3], u) = self.segment(t);
+
+        let u2 = u * u;
+        let u3 = u2 * u;
+
+        (p0 * (-u3 + 2.0 * u2 - u)
+            + p1 * (3.0 * u3 - 5.0 * u2 + 2.0)
+            + p2 * (-3.0 * u3 + 4.0 * u2 + u)
+            + p3 * (u3 - u2))
+            * 0.5
+    }
+
+    // Derivative of `sample` with respect to t, pointing along the path.
+    pub fn tangent(&self, t: f32) -> Vector3<f32> {
+        let ([p0, p1, p2, p3], u) = self.segment(t);
+
+        let u2 = u * u;
+        let segments = (self.points.len() - 1) as f32;
+
+        (p0 * (-3.0 * u2 + 4.0 * u - 1.0)
+            + p1 * (9.0 * u2 - 10.0 * u)
+            + p2 * (-9.0 * u2 + 8.0 * u + 1.0)
+            + p3 * (3.0 * u2 - 2.0 * u))
+            * (0.5 * segments)
+    }
+
+    fn segment(&self, t: f32) -> ([Vector3<f32>; 4], f32) {
+        let last = self.points.len() - 1;
+
+        let position = t.clamp(0.0, 1.0) * last as f32;
+        let i = (position.floor() as usize).min(last - 1);
+
+        let point = |index: usize| self.points[index.min(last)];
+
+        (
+            [
+                point(i.saturating_sub(1)),
+                point(i),
+                point(i + 1),
+                point(i + 2),
+            ],
+            position - i as f32,
+        )
+    }
+}
+
+#[test]
+fn test_tween_linear() {
+    let tween = Tween::new(
+        Vector3::new(0.0, 2.0, -4.0),
+        Vector3::new(2.0, 4.0, 4.0),
+        2.0,
+        Easing::Linear,
+    );
+
+    assert_eq!(tween.sample(0.0), tween.from);
+    assert_eq!(tween.sample(1.0), Vector3::new(1.0, 3.0, 0.0));
+    assert_eq!(tween.sample(2.0), tween.to);
+
+    assert_eq!(tween.sample(-1.0), tween.from);
+    assert_eq!(tween.sample(10.0), tween.to);
+
+    assert_eq!(Tween::new(1.0, 5.0, 0.0, Easing::Linear).sample(0.0), 5.0);
+}
+
+#[test]
+fn test_tween_easing() {
+    let ease_in_out = Tween::new(0.0, 1.0, 1.0, Easing::EaseInOut);
+
+    assert_eq!(ease_in_out.sample(0.5), 0.5);
+
+    for step in 1..10 {
+        let t = step as f32 * 0.05;
+        let sum = ease_in_out.sample(0.5 - t) + ease_in_out.sample(0.5 + t);
+
+        assert!((sum - 1.0).abs() < 1e-6);
+    }
+
+    let ease_out = Tween::new(0.0, 1.0, 1.0, Easing::EaseOut);
+
+    assert!(ease_out.sample(0.5) > 0.5);
+    assert_eq!(ease_out.sample(1.0), 1.0);
+}
+
+#[test]
+fn test_catmull_rom() {
+    let points = vec![
+        Vector3::new(0.0, 0.0, 0.0),
+        Vector3::new(1.0, 2.0, 0.0),
+        Vector3::new(3.0, 2.0, 1.0),
+        Vector3::new(4.0, 0.0, 1.0),
+        Vector3::new(6.0, -1.0, 0.0),
+    ];
+
+    let spline = CatmullRom::new(points.clone()).unwrap();
+
+    for (i, point) in points.iter().enumerate() {
+        let sample = spline.sample(i as f32 / 4.0);
+        assert!(sample.distance(*point) < 1e-5);
+    }
+
+    for i in 1..4 {
+        let t = i as f32 / 4.0;
+        let tangent = spline.tangent(t);
+        let step = spline.sample(t + 1e-3) - spline.sample(t - 1e-3);
+
+        assert!(tangent.dot(step) > 0.0);
+        assert!(tangent.normalize().distance(step.normalize()) < 1e-2);
+    }
+
+    let line = CatmullRom::new(vec![
+        Vector3::new(0.0, 0.0, 0.0),
+        Vector3::new(2.0, 0.0, 0.0),
+    ])
+    .unwrap();
+
+    assert!(line.sample(0.5).distance(Vector3::new(1.0, 0.0, 0.0)) < 1e-6);
+    assert!(
+        line.tangent(0.5)
+            .normalize()
+            .distance(Vector3::new(1.0, 0.0, 0.0))
+            < 1e-6
+    );
+
+    assert!(CatmullRom::new(vec![Vector3::new(0.0, 0.0, 0.0)]).is_none());
+}
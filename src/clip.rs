@@ -0,0 +1,115 @@
+use crate::math::vectors::{Vector3, Vector4};
+use crate::mesh::Vertex;
+
+// Clip-space planes as signed distances, inside when >= 0. Depth runs from 0
+// at the near plane to -w at the far plane, matching Matrix4::projection.
+const PLANES: [fn(Vector4<f32>) -> f32; 6] = [
+    |v| v.w + v.x,
+    |v| v.w - v.x,
+    |v| v.w + v.y,
+    |v| v.w - v.y,
+    |v| v.w + v.z,
+    |v| -v.z,
+];
+
+// Sutherland–Hodgman against all six planes, then a fan back into triangles
+// with the winding of the input. Attributes of new vertices come from
+// Vertex::blend along the clipped edge.
+#[allow(dead_code)]
+pub fn clip_to_frustum<V: Vertex>(triangle: (V, V, V)) -> Vec<(V, V, V)> {
+    let mut polygon = vec![triangle.0, triangle.1, triangle.2];
+
+    for plane in PLANES {
+        if polygon.is_empty() {
+            break;
+        }
+
+        polygon = clip_polygon(&polygon, plane);
+    }
+
+    (1..polygon.len().saturating_sub(1))
+        .map(|i| (polygon[0], polygon[i], polygon[i + 1]))
+        .collect()
+}
+
+fn clip_polygon<V: Vertex>(polygon: &[V], plane: fn(Vector4<f32>) -> f32) -> Vec<V> {
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+
+    let mut previous = polygon[polygon.len() - 1];
+    let mut previous_distance = plane(previous.position());
+
+    for &current in polygon {
+        let distance = plane(current.position());
+
+        if (distance >= 0.0) != (previous_distance >= 0.0) {
+            let t = previous_distance / (previous_distance - distance);
+            clipped.push(V::blend(
+                (previous, current, current),
+                Vector3::new(1.0 - t, t, 0.0),
+            ));
+        }
+
+        if distance >= 0.0 {
+            clipped.push(current);
+        }
+
+        previous = current;
+        previous_distance = distance;
+    }
+
+    clipped
+}
+
+#[test]
+fn test_clip_inside_and_outside() {
+    let inside = (
+        Vector4::new(-0.5, -0.5, -0.5, 1.0),
+        Vector4::new(0.5, -0.5, -0.5, 1.0),
+        Vector4::new(0.0, 0.5, -0.5, 1.0),
+    );
+
+    assert_eq!(clip_to_frustum(inside), vec![inside]);
+
+    let outside = (
+        Vector4::new(2.0, -0.5, -0.5, 1.0),
+        Vector4::new(3.0, -0.5, -0.5, 1.0),
+        Vector4::new(2.5, 0.5, -0.5, 1.0),
+    );
+
+    assert!(clip_to_frustum(outside).is_empty());
+}
+
+#[test]
+fn test_clip_right_plane() {
+    let triangle = (
+        Vector4::new(0.0, -0.5, -0.5, 1.0),
+        Vector4::new(3.0, 0.0, -0.5, 1.0),
+        Vector4::new(0.0, 0.5, -0.5, 1.0),
+    );
+
+    let clipped = clip_to_frustum(triangle);
+    assert_eq!(clipped.len(), 2);
+
+    let vertices: Vec<_> = clipped.iter().flat_map(|&(a, b, c)| [a, b, c]).collect();
+
+    for vertex in &vertices {
+        for plane in PLANES {
+            assert!(plane(*vertex) >= -1e-6);
+        }
+    }
+
+    let on_plane: Vec<_> = vertices
+        .iter()
+        .filter(|vertex| (vertex.x - vertex.w).abs() < 1e-6)
+        .collect();
+
+    assert!(on_plane.iter().any(|v| (v.y + 1.0 / 3.0).abs() < 1e-6));
+    assert!(on_plane.iter().any(|v| (v.y - 1.0 / 3.0).abs() < 1e-6));
+
+    let area = |(a, b, c): (Vector4<f32>, Vector4<f32>, Vector4<f32>)| {
+        let a: Vector3<f32> = a.into();
+        (Vector3::from(b) - a).cross(Vector3::from(c) - a).z
+    };
+
+    assert!(clipped.iter().all(|&triangle| area(triangle) > 0.0));
+}
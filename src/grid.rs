@@ -0,0 +1,214 @@
+use crate::math::matrices::Matrix4;
+use crate::math::vectors::{Vector2, Vector3, Vector4};
+use crate::raster::{Line3, NEAR_W_EPSILON};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GridLine {
+    pub start: Vector3<f32>,
+    pub end: Vector3<f32>,
+    pub color: u32,
+}
+
+// Lines on each side of an axis; keeps lines() to a few MB at most.
+const MAX_LINES_PER_SIDE: i32 = 10_000;
+
+#[derive(Copy, Clone, Debug)]
+pub struct Grid {
+    pub spacing: f32,
+    pub extent: f32,
+    pub color: u32,
+    pub axis_color: u32,
+}
+
+#[allow(dead_code)]
+impl Grid {
+    pub fn new(spacing: f32, extent: f32, color: u32, axis_color: u32) -> Option<Self> {
+        if !(spacing > 0.0 && spacing.is_finite() && extent >= 0.0 && extent.is_finite()) {
+            return None;
+        }
+
+        if (extent / spacing).floor() > MAX_LINES_PER_SIDE as f32 {
+            return None;
+        }
+
+        Some(Self {
+            spacing,
+            extent,
+            color,
+            axis_color,
+        })
+    }
+
+    pub fn lines(&self) -> Vec<GridLine> {
+        let count = (self.extent / self.spacing).floor() as i32;
+        let mut lines = Vec::with_capacity((4 * count + 2) as usize);
+
+        for i in -count..=count {
+            let offset = i as f32 * self.spacing;
+            let color = if i == 0 { self.axis_color } else { self.color };
+
+            lines.push(GridLine {
+                start: Vector3::new(offset, 0.0, -self.extent),
+                end: Vector3::new(offset, 0.0, self.extent),
+                color,
+            });
+            lines.push(GridLine {
+                start: Vector3::new(-self.extent, 0.0, offset),
+                end: Vector3::new(self.extent, 0.0, offset),
+                color,
+            });
+        }
+
+        lines
+    }
+
+    pub fn draw<F>(&self, matrix: Matrix4<f32>, size: Vector2<i32>, mut plot: F)
+    where
+        F: FnMut(Vector2<i32>, f32, u32),
+    {
+        for line in self.lines() {
+            let start = matrix * Vector4::from(line.start);
+            let end = matrix * Vector4::from(line.end);
+
+            let Some((start, end)) = clip_near(start, end) else {
+                continue;
+            };
+
+            let Some(line3) = Line3::clipped(start, end, size) else {
+                continue;
+            };
+
+            for (position, depth) in line3 {
+                plot(position, depth, line.color);
+            }
+        }
+    }
+}
+
+// Cuts the segment where w crosses NEAR_W_EPSILON, before the divide.
+fn clip_near(start: Vector4<f32>, end: Vector4<f32>) -> Option<(Vector4<f32>, Vector4<f32>)> {
+    let (ds, de) = (start.w - NEAR_W_EPSILON, end.w - NEAR_W_EPSILON);
+
+    if ds < 0.0 && de < 0.0 {
+        return None;
+    }
+
+    let cut = |t: f32| {
+        Vector4::new(
+            start.x + (end.x - start.x) * t,
+            start.y + (end.y - start.y) * t,
+            start.z + (end.z - start.z) * t,
+            NEAR_W_EPSILON,
+        )
+    };
+
+    if ds < 0.0 {
+        Some((cut(ds / (ds - de)), end))
+    } else if de < 0.0 {
+        Some((start, cut(ds / (ds - de))))
+    } else {
+        Some((start, end))
+    }
+}
+
+#[test]
+fn test_grid_lines() {
+    let grid = Grid::new(1.0, 2.0, 0xFF808080, 0xFFFF0000).unwrap();
+    let lines = grid.lines();
+
+    assert_eq!(lines.len(), 10);
+
+    let axes: Vec<_> = lines
+        .iter()
+        .filter(|line| line.color == grid.axis_color)
+        .collect();
+
+    assert_eq!(axes.len(), 2);
+    assert!(axes
+        .iter()
+        .all(|line| (line.start.x == 0.0 && line.end.x == 0.0)
+            || (line.start.z == 0.0 && line.end.z == 0.0)));
+
+    assert_eq!(Grid::new(0.5, 2.0, 0, 0).unwrap().lines().len(), 18);
+}
+
+#[test]
+fn test_grid_invalid() {
+    assert!(Grid::new(0.0, 2.0, 0, 0).is_none());
+    assert!(Grid::new(-1.0, 2.0, 0, 0).is_none());
+    assert!(Grid::new(f32::NAN, 2.0, 0, 0).is_none());
+    assert!(Grid::new(f32::INFINITY, 2.0, 0, 0).is_none());
+    assert!(Grid::new(1.0, -2.0, 0, 0).is_none());
+    assert!(Grid::new(1.0, f32::NAN, 0, 0).is_none());
+    assert!(Grid::new(1e-9, 1e9, 0, 0).is_none());
+    assert!(Grid::new(1.0, 5e8, 0, 0).is_none());
+
+    let widest = Grid::new(1.0, MAX_LINES_PER_SIDE as f32, 0, 0).unwrap();
+    assert_eq!(widest.lines().len(), 4 * MAX_LINES_PER_SIDE as usize + 2);
+
+    assert_eq!(Grid::new(1.0, 0.0, 0, 0).unwrap().lines().len(), 2);
+}
+
+#[test]
+fn test_grid_draw() {
+    let grid = Grid::new(1.0, 2.0, 0xFF808080, 0xFFFF0000).unwrap();
+
+    let matrix = Matrix4::new(
+        Vector4::new(10.0, 0.0, 0.0, 50.0),
+        Vector4::new(0.0, 0.0, 10.0, 50.0),
+        Vector4::new(0.0, 0.0, 0.0, 0.5),
+        Vector4::new(0.0, 0.0, 0.0, 1.0),
+    );
+
+    let mut points = Vec::new();
+    grid.draw(matrix, Vector2::new(100, 100), |position, depth, color| {
+        points.push((position, depth, color))
+    });
+
+    assert!(points.iter().all(|(position, depth, _)| {
+        (30..=70).contains(&position.x) && (30..=70).contains(&position.y) && *depth == 0.5
+    }));
+
+    let color_at = |x: i32, y: i32| {
+        points
+            .iter()
+            .find(|(position, _, _)| *position == Vector2::new(x, y))
+            .map(|(_, _, color)| *color)
+    };
+
+    assert_eq!(color_at(50, 35), Some(grid.axis_color));
+    assert_eq!(color_at(65, 50), Some(grid.axis_color));
+    assert_eq!(color_at(40, 35), Some(grid.color));
+    assert_eq!(color_at(45, 35), None);
+}
+
+#[test]
+fn test_grid_draw_under_camera() {
+    let size = Vector2::new(64, 64);
+    let grid = Grid::new(1.0, 2.0, 0xFF808080, 0xFFFF0000).unwrap();
+
+    let mut view = Matrix4::identity();
+    view.y.w = -1.0;
+
+    let matrix = Matrix4::viewport(size)
+        * Matrix4::projection(1.0, std::f32::consts::FRAC_PI_2, 0.1, 100.0)
+        * view;
+
+    let mut points = Vec::new();
+    grid.draw(matrix, size, |position, _, color| {
+        points.push((position, color))
+    });
+
+    assert!(points.len() < grid.lines().len() * 2 * 64);
+    assert!(points.iter().all(|(position, _)| {
+        (0..size.x).contains(&position.x) && (0..size.y).contains(&position.y)
+    }));
+
+    // The z axis runs from in front of the camera to behind it; its near
+    // part reaches the bottom edge instead of being dropped.
+    assert!(points
+        .iter()
+        .any(|(position, color)| *color == grid.axis_color
+            && position.x == 32
+            && position.y == size.y - 1));
+}
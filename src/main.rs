@@ -1,5 +1,12 @@
+mod animation;
+mod clip;
+mod grid;
 mod math;
+mod mesh;
+mod noise;
+mod primitives;
 mod raster;
+mod shading;
 mod triangles;
 
 use image::open;
@@ -8,18 +15,24 @@ use image::Rgb;
 use math::matrices::Matrix4;
 use math::vectors::Vector3;
 use math::vectors::Vector4;
+use mesh::{Mesh, Vertex};
+use raster::DepthFunc;
+use raster::Line3;
 use raster::Triangle;
-use std::ops::Add;
+use shading::Combine;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::num::NonZeroU32;
+use std::ops::{Add, Mul, Sub};
 use std::rc::Rc;
 use winit::application::ApplicationHandler;
 use winit::dpi::PhysicalSize;
 use winit::event::WindowEvent;
 use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
 use winit::window::WindowAttributes;
-use winit::window::{Window, WindowId};
+use winit::window::{CursorGrabMode, Fullscreen, Window, WindowId};
 
 use crate::math::vectors::Vector2;
-use softbuffer::Buffer;
 
 struct App {
     state: Option<State>,
@@ -27,6 +40,9 @@ struct App {
     h: u32,
     time: std::time::SystemTime,
     image: ImageBuffer<Rgb<u8>, Vec<u8>>,
+    combine: Combine,
+    fog: Option<Fog>,
+    gamma_correct: bool,
 }
 
 #[allow(dead_code)]
@@ -36,6 +52,7 @@ struct RenderContext {
 
     framebuffer: Vec<u32>,
     depthbuffer: Vec<u8>,
+    depth_func: DepthFunc,
 }
 
 #[allow(dead_code)]
@@ -46,9 +63,14 @@ impl RenderContext {
             height,
             framebuffer: vec![0; (width * height) as usize],
             depthbuffer: vec![u8::max_value(); (width * height) as usize],
+            depth_func: DepthFunc::LessEqual,
         }
     }
 
+    fn set_depth_func(&mut self, depth_func: DepthFunc) {
+        self.depth_func = depth_func;
+    }
+
     fn resize(&mut self, new_size: PhysicalSize<u32>) {
         self.width = new_size.width;
         self.height = new_size.height;
@@ -72,7 +94,7 @@ impl RenderContext {
             return false;
         };
 
-        if z > *depth {
+        if !self.depth_func.test(z, *depth) {
             return false;
         }
 
@@ -90,83 +112,346 @@ impl RenderContext {
     fn framebuffer(&self) -> &[u32] {
         self.framebuffer.as_ref()
     }
+
+    fn depth_value(z: f32) -> u8 {
+        (z.clamp(0.0, 1.0) * u8::MAX as f32) as u8
+    }
+
+    fn fill_triangle(&mut self, triangle: Triangle, color: u32) {
+        let size = Vector2::new(self.width as i32, self.height as i32);
+
+        for frag in triangle.into_iter().clamped(size) {
+            self.draw(
+                frag.position.x as u32,
+                frag.position.y as u32,
+                Self::depth_value(frag.position.z),
+                color,
+            );
+        }
+    }
+
+    fn draw_hidden_line(&mut self, triangles: &[[Vector4<f32>; 3]], fill: u32, edge: u32) {
+        let visible: Vec<_> = triangles
+            .iter()
+            .filter_map(|&vertices| Some((vertices, Triangle::new(vertices)?)))
+            .collect();
+
+        for &(_, triangle) in &visible {
+            self.fill_triangle(triangle, fill);
+        }
+
+        let depth_func = self.depth_func;
+        self.depth_func = DepthFunc::LessEqual;
+
+        let size = Vector2::new(self.width as i32, self.height as i32);
+
+        for (vertices, _) in &visible {
+            for (start, end) in [(0, 1), (1, 2), (2, 0)] {
+                let Some(line) = Line3::clipped(vertices[start], vertices[end], size) else {
+                    continue;
+                };
+
+                for (position, depth) in line {
+                    let z = Self::depth_value(depth).saturating_sub(EDGE_DEPTH_BIAS);
+                    self.draw(position.x as u32, position.y as u32, z, edge);
+                }
+            }
+        }
+
+        self.depth_func = depth_func;
+    }
+}
+
+const EDGE_DEPTH_BIAS: u8 = 2;
+
+const PIXEL_SIZE: u32 = 2;
+
+// The window State presents to; lets frames be presented without a window.
+trait Presenter {
+    fn resize(&mut self, width: NonZeroU32, height: NonZeroU32) -> Result<(), PresentError>;
+    fn present(&mut self, frame: &[u32]) -> Result<(), PresentError>;
+    fn request_redraw(&self);
+    fn set_title(&self, title: &str);
+    // Returns whether the platform accepted the grab.
+    fn set_cursor_grab(&self, grab: bool) -> bool;
+    fn set_fullscreen(&self, fullscreen: bool);
 }
 
-struct State {
+struct WindowPresenter {
     window: Rc<Window>,
     surface: softbuffer::Surface<Rc<Window>, Rc<Window>>,
-    size: PhysicalSize<u32>,
 }
 
-impl State {
+impl WindowPresenter {
     fn new(window: &Rc<Window>) -> Option<Self> {
         let context = softbuffer::Context::new(window.clone()).ok()?;
-        let mut surface = softbuffer::Surface::new(&context, window.clone()).ok()?;
-        let size = PhysicalSize::<u32>::new(300, 300);
+        let surface = softbuffer::Surface::new(&context, window.clone()).ok()?;
 
-        surface
-            .resize(
-                size.width.try_into().unwrap(),
-                size.height.try_into().unwrap(),
-            )
-            .ok()?;
         Some(Self {
             window: window.clone(),
             surface,
-            size,
         })
     }
+}
 
-    fn resize(&mut self, size: PhysicalSize<u32>) {
-        self.size = size;
+impl Presenter for WindowPresenter {
+    fn resize(&mut self, width: NonZeroU32, height: NonZeroU32) -> Result<(), PresentError> {
         self.surface
+            .resize(width, height)
+            .map_err(|error| PresentError::Resize(error.into()))
+    }
+
+    fn present(&mut self, frame: &[u32]) -> Result<(), PresentError> {
+        let mut buffer = self
+            .surface
+            .buffer_mut()
+            .map_err(|error| PresentError::BufferLock(error.into()))?;
+
+        buffer.copy_from_slice(frame);
+        buffer
+            .present()
+            .map_err(|error| PresentError::Present(error.into()))
+    }
+
+    fn request_redraw(&self) {
+        self.window.request_redraw();
+    }
+
+    fn set_title(&self, title: &str) {
+        self.window.set_title(title);
+    }
+
+    fn set_cursor_grab(&self, grab: bool) -> bool {
+        if !grab {
+            return self.window.set_cursor_grab(CursorGrabMode::None).is_ok();
+        }
+
+        // Not every platform can lock the cursor; confining is the fallback.
+        self.window
+            .set_cursor_grab(CursorGrabMode::Locked)
+            .or_else(|_| self.window.set_cursor_grab(CursorGrabMode::Confined))
+            .is_ok()
+    }
+
+    fn set_fullscreen(&self, fullscreen: bool) {
+        self.window
+            .set_fullscreen(fullscreen.then_some(Fullscreen::Borderless(None)));
+    }
+}
+
+struct State<P: Presenter = WindowPresenter> {
+    presenter: P,
+    frame: Vec<u32>,
+    size: PhysicalSize<u32>,
+    scale_factor: f64,
+    pixel_aspect: f32,
+}
+
+impl State {
+    fn new(window: &Rc<Window>) -> Option<Self> {
+        let mut presenter = WindowPresenter::new(window)?;
+        let size = PhysicalSize::<u32>::new(300, 300);
+
+        presenter
             .resize(
                 size.width.try_into().unwrap(),
                 size.height.try_into().unwrap(),
             )
-            .unwrap();
+            .ok()?;
+        Some(State::with_presenter(
+            presenter,
+            size,
+            window.scale_factor(),
+        ))
+    }
+
+    fn pixel_size(scale_factor: f64) -> u32 {
+        ((PIXEL_SIZE as f64 * scale_factor).round() as u32).max(1)
+    }
+
+    // Width and height of one framebuffer pixel on screen; pixel_aspect
+    // stretches it horizontally (> 1) or vertically (< 1).
+    fn pixel_extent(scale_factor: f64, pixel_aspect: f32) -> PhysicalSize<u32> {
+        let size = Self::pixel_size(scale_factor);
+        let pixel_aspect = if Self::valid_pixel_aspect(pixel_aspect) {
+            pixel_aspect
+        } else {
+            1.0
+        };
+        let stretched = |size: f32| (size.round() as u32).max(1);
+
+        if pixel_aspect >= 1.0 {
+            PhysicalSize::new(stretched(size as f32 * pixel_aspect), size)
+        } else {
+            PhysicalSize::new(size, stretched(size as f32 / pixel_aspect))
+        }
     }
 
-    fn draw<F>(&mut self, draw: F)
+    fn valid_pixel_aspect(pixel_aspect: f32) -> bool {
+        pixel_aspect.is_finite() && pixel_aspect > 0.0
+    }
+
+    fn surface_size(size: PhysicalSize<u32>) -> Result<(NonZeroU32, NonZeroU32), PresentError> {
+        match (NonZeroU32::new(size.width), NonZeroU32::new(size.height)) {
+            (Some(width), Some(height)) => Ok((width, height)),
+            _ => Err(PresentError::ZeroSize),
+        }
+    }
+}
+
+impl<P: Presenter> State<P> {
+    // Expects the presenter to be sized to `size` already.
+    fn with_presenter(presenter: P, size: PhysicalSize<u32>, scale_factor: f64) -> Self {
+        Self {
+            presenter,
+            frame: vec![0; (size.width * size.height) as usize],
+            size,
+            scale_factor,
+            pixel_aspect: 1.0,
+        }
+    }
+
+    #[allow(dead_code)]
+    fn set_title(&self, title: &str) {
+        self.presenter.set_title(title);
+    }
+
+    #[allow(dead_code)]
+    fn set_cursor_grab(&self, grab: bool) -> bool {
+        self.presenter.set_cursor_grab(grab)
+    }
+
+    #[allow(dead_code)]
+    fn set_fullscreen(&self, fullscreen: bool) {
+        self.presenter.set_fullscreen(fullscreen);
+    }
+
+    fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+    }
+
+    // Ignores aspects that are not finite and positive; returns whether it was set.
+    #[allow(dead_code)]
+    fn set_pixel_aspect(&mut self, pixel_aspect: f32) -> bool {
+        if !State::valid_pixel_aspect(pixel_aspect) {
+            return false;
+        }
+
+        self.pixel_aspect = pixel_aspect;
+        true
+    }
+
+    // A zero size (minimized window) is only recorded; drawing then reports
+    // PresentError::ZeroSize, so the next non-zero resize requests a redraw
+    // to get the loop going again.
+    fn resize(&mut self, size: PhysicalSize<u32>) -> Result<(), PresentError> {
+        self.size = size;
+
+        if let Ok((width, height)) = State::surface_size(size) {
+            self.presenter.resize(width, height)?;
+            self.frame.resize((width.get() * height.get()) as usize, 0);
+            self.presenter.request_redraw();
+        }
+
+        Ok(())
+    }
+
+    fn draw<F>(&mut self, draw: F) -> Result<(), PresentError>
+    where
+        F: Fn(&mut Drawer),
+    {
+        self.present_only(draw)?;
+        self.presenter.request_redraw();
+
+        Ok(())
+    }
+
+    // Draws and presents a frame without scheduling another one, for apps
+    // that only redraw when something changes.
+    fn present_only<F>(&mut self, draw: F) -> Result<(), PresentError>
     where
         F: Fn(&mut Drawer),
     {
-        let buffer = self.surface.buffer_mut().unwrap();
+        State::surface_size(self.size)?;
+
         let mut drawer = Drawer {
             size: self.size,
-            buffer,
-            pixel_size: 2,
+            buffer: &mut self.frame,
+            pixel_size: State::pixel_extent(self.scale_factor, self.pixel_aspect),
         };
 
         draw(&mut drawer);
-        Self::update(&self.window, drawer.buffer)
+
+        self.presenter.present(&self.frame)
     }
 
-    fn update(window: &Rc<Window>, buffer: Buffer<Rc<Window>, Rc<Window>>) {
-        buffer.present().unwrap();
-        window.request_redraw();
+    // Copy of the last drawn frame, row-major at `size`, for screenshots.
+    #[allow(dead_code)]
+    fn capture(&self) -> Vec<u32> {
+        self.frame.clone()
+    }
+}
+
+#[derive(Debug)]
+enum PresentError {
+    ZeroSize,
+    Resize(Box<dyn Error>),
+    BufferLock(Box<dyn Error>),
+    Present(Box<dyn Error>),
+}
+
+impl Display for PresentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PresentError::ZeroSize => write!(f, "surface has a zero size"),
+            PresentError::Resize(_) => write!(f, "failed to resize the surface"),
+            PresentError::BufferLock(_) => write!(f, "failed to lock the surface buffer"),
+            PresentError::Present(_) => write!(f, "failed to present the surface buffer"),
+        }
+    }
+}
+
+impl Error for PresentError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PresentError::ZeroSize => None,
+            PresentError::Resize(error)
+            | PresentError::BufferLock(error)
+            | PresentError::Present(error) => Some(error.as_ref()),
+        }
+    }
+}
+
+// Prints the error followed by its chain of sources.
+fn report(error: &dyn Error) {
+    eprintln!("error: {error}");
+
+    let mut source = error.source();
+    while let Some(error) = source {
+        eprintln!("  caused by: {error}");
+        source = error.source();
     }
 }
 
 struct Drawer<'a> {
-    buffer: Buffer<'a, Rc<Window>, Rc<Window>>,
+    buffer: &'a mut [u32],
     size: PhysicalSize<u32>,
-    pixel_size: u32,
+    pixel_size: PhysicalSize<u32>,
 }
 
 impl<'a> Drawer<'a> {
     fn draw_pixel(&mut self, x: u32, y: u32, color: u32) -> bool {
-        let begin_x = x * self.pixel_size;
-        let begin_y = y * self.pixel_size;
+        let begin_x = x.saturating_mul(self.pixel_size.width);
+        let begin_y = y.saturating_mul(self.pixel_size.height);
 
-        if begin_x >= self.size.width - self.pixel_size
-            || begin_y >= self.size.height - self.pixel_size
+        if begin_x >= self.size.width.saturating_sub(self.pixel_size.width)
+            || begin_y >= self.size.height.saturating_sub(self.pixel_size.height)
         {
             return false;
         }
 
-        let range_x = begin_x..begin_x + self.pixel_size;
-        let range_y = begin_y..begin_y + self.pixel_size;
+        let range_x = begin_x..begin_x + self.pixel_size.width;
+        let range_y = begin_y..begin_y + self.pixel_size.height;
 
         for x in range_x {
             for y in range_y.clone() {
@@ -178,7 +463,7 @@ impl<'a> Drawer<'a> {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 struct Color {
     r: f32,
     g: f32,
@@ -207,22 +492,177 @@ impl Color {
             g: self.g * factor,
         }
     }
+
+    fn to_linear(self) -> Self {
+        let channel = |c: f32| {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        Self {
+            r: channel(self.r),
+            g: channel(self.g),
+            b: channel(self.b),
+            a: self.a,
+        }
+    }
+
+    fn to_srgb(self) -> Self {
+        let channel = |c: f32| {
+            if c <= 0.0031308 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            }
+        };
+
+        Self {
+            r: channel(self.r),
+            g: channel(self.g),
+            b: channel(self.b),
+            a: self.a,
+        }
+    }
+
+    fn luma(self) -> f32 {
+        0.299 * self.r + 0.587 * self.g + 0.114 * self.b
+    }
+
+    fn adjust_brightness(self, delta: f32) -> Self {
+        self + Color::from_rgb(delta, delta, delta)
+    }
+
+    // Scales the distance of each channel from mid-gray (0.5).
+    fn adjust_contrast(self, factor: f32) -> Self {
+        let pivot = Color {
+            a: self.a,
+            ..Color::from_rgb(0.5, 0.5, 0.5)
+        };
+
+        pivot + (self - pivot) * factor
+    }
+
+    // Scales the distance of each channel from the color's own luma.
+    fn adjust_saturation(self, factor: f32) -> Self {
+        let luma = self.luma();
+        let gray = Color {
+            a: self.a,
+            ..Color::from_rgb(luma, luma, luma)
+        };
+
+        gray + (self - gray) * factor
+    }
+
+    // Barycentric blend of sRGB colors done in linear space.
+    fn interpolate_srgb(coefs: Vector3<f32>, colors: (Color, Color, Color)) -> Self {
+        coefs
+            .interpolate((
+                colors.0.to_linear(),
+                colors.1.to_linear(),
+                colors.2.to_linear(),
+            ))
+            .to_srgb()
+    }
 }
 
+// Add, Sub and Mul<f32> work on the rgb channels and keep the alpha of the
+// left operand. Mul<Color> multiplies every channel, alpha included.
 impl Add<Color> for Color {
     type Output = Color;
 
     fn add(self, another: Self) -> Self {
         Self {
-            r: (self.r + another.r) / 2.0,
-            g: (self.g + another.g) / 2.0,
-            b: (self.b + another.b) / 2.0,
+            r: self.r + another.r,
+            g: self.g + another.g,
+            b: self.b + another.b,
+            a: self.a,
+        }
+    }
+}
+
+impl Sub<Color> for Color {
+    type Output = Color;
 
-            a: (self.a + another.a) / 2.0,
+    fn sub(self, another: Self) -> Self {
+        Self {
+            r: self.r - another.r,
+            g: self.g - another.g,
+            b: self.b - another.b,
+            a: self.a,
         }
     }
 }
 
+impl Mul<f32> for Color {
+    type Output = Color;
+
+    fn mul(self, factor: f32) -> Self {
+        Self {
+            r: self.r * factor,
+            g: self.g * factor,
+            b: self.b * factor,
+            a: self.a,
+        }
+    }
+}
+
+impl Mul<Color> for Color {
+    type Output = Color;
+
+    fn mul(self, another: Self) -> Self {
+        Self {
+            r: self.r * another.r,
+            g: self.g * another.g,
+            b: self.b * another.b,
+            a: self.a * another.a,
+        }
+    }
+}
+
+impl From<Color> for Vector4<f32> {
+    fn from(color: Color) -> Self {
+        Vector4::new(color.r, color.g, color.b, color.a)
+    }
+}
+
+impl From<Vector4<f32>> for Color {
+    fn from(value: Vector4<f32>) -> Self {
+        Self {
+            r: value.x,
+            g: value.y,
+            b: value.z,
+            a: value.w,
+        }
+    }
+}
+
+// Blends toward `color` linearly between `near` and `far`, measured as view
+// distance (Fragment::w); with `far <= near` it switches fully on at `near`.
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Fog {
+    color: Color,
+    near: f32,
+    far: f32,
+}
+
+impl Fog {
+    fn apply(&self, color: Color, distance: f32) -> Color {
+        let amount = if self.far > self.near {
+            ((distance - self.near) / (self.far - self.near)).clamp(0.0, 1.0)
+        } else if distance >= self.near {
+            1.0
+        } else {
+            0.0
+        };
+
+        color + (self.color - color) * amount
+    }
+}
+
 impl ApplicationHandler for App {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         let window = make_window(event_loop);
@@ -234,7 +674,7 @@ impl ApplicationHandler for App {
             return;
         };
 
-        if id != state.window.id() {
+        if id != state.presenter.window.id() {
             return;
         }
 
@@ -243,18 +683,18 @@ impl ApplicationHandler for App {
                 event_loop.exit();
             }
 
-            WindowEvent::RedrawRequested => state.draw(|draw| {
-                let w = self.w as i32 / draw.pixel_size as i32;
-                let h = self.h as i32 / draw.pixel_size as i32;
+            WindowEvent::RedrawRequested => match state.draw(|draw| {
+                let w = self.w as i32 / draw.pixel_size.width as i32;
+                let h = self.h as i32 / draw.pixel_size.height as i32;
 
                 #[derive(Copy, Clone)]
-                struct Vertex {
+                struct TexturedVertex {
                     pub position: Vector4<f32>,
                     pub color: Vector3<f32>,
                     pub uv: Vector2<f32>,
                 }
 
-                impl Vertex {
+                impl TexturedVertex {
                     fn new(x: f32, y: f32, z: f32, r: f32, g: f32, b: f32, u: f32, v: f32) -> Self {
                         Self {
                             position: Vector4::new(x, y, z, 1.0),
@@ -264,20 +704,44 @@ impl ApplicationHandler for App {
                     }
                 }
 
-                let vertices = [
-                    Vertex::new(-1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0),
-                    Vertex::new(1.0, 1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0),
-                    Vertex::new(1.0, -1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0),
-                    Vertex::new(-1.0, -1.0, 0.0, 1.0, 1.0, 1.0, 0.0, 1.0),
-                ];
+                impl Vertex for TexturedVertex {
+                    fn position(&self) -> Vector4<f32> {
+                        self.position
+                    }
+
+                    fn blend(vertices: (Self, Self, Self), coefs: Vector3<f32>) -> Self {
+                        Self {
+                            position: coefs.interpolate((
+                                vertices.0.position,
+                                vertices.1.position,
+                                vertices.2.position,
+                            )),
+                            color: coefs.interpolate((
+                                vertices.0.color,
+                                vertices.1.color,
+                                vertices.2.color,
+                            )),
+                            uv: coefs.interpolate((vertices.0.uv, vertices.1.uv, vertices.2.uv)),
+                        }
+                    }
+                }
 
-                let mesh = [(0, 1, 2), (2, 3, 0), (2, 1, 0), (0, 3, 2)];
+                let mesh = Mesh::new(
+                    vec![
+                        TexturedVertex::new(-1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0),
+                        TexturedVertex::new(1.0, 1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0),
+                        TexturedVertex::new(1.0, -1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0),
+                        TexturedVertex::new(-1.0, -1.0, 0.0, 1.0, 1.0, 1.0, 0.0, 1.0),
+                    ],
+                    vec![(0, 1, 2), (2, 3, 0), (2, 1, 0), (0, 3, 2)],
+                );
 
                 let time = self.time.elapsed().unwrap().as_secs_f32();
                 let rotate = Matrix4::<f32>::rotation_x(-time);
 
                 let aspect = if h != 0 && w != 0 {
-                    w as f32 / h as f32
+                    (w as u32 * draw.pixel_size.width) as f32
+                        / (h as u32 * draw.pixel_size.height) as f32
                 } else {
                     1.0
                 };
@@ -291,20 +755,14 @@ impl ApplicationHandler for App {
 
                 let matrix = viewport * projection * look * rotate;
 
-                let triangle_iter = mesh.into_iter().filter_map(|indices| {
-                    let triangle = (
-                        vertices[indices.0],
-                        vertices[indices.1],
-                        vertices[indices.2],
-                    );
-
+                let triangle_iter = mesh.triangles().filter_map(|triangle| {
                     let ndc = [
-                        matrix * triangle.0.position,
-                        matrix * triangle.1.position,
-                        matrix * triangle.2.position,
+                        matrix * triangle.0.position(),
+                        matrix * triangle.1.position(),
+                        matrix * triangle.2.position(),
                     ];
 
-                    let iter = Triangle::new(ndc)?.into_iter();
+                    let iter = Triangle::new(ndc)?.into_iter().clamped(Vector2::new(w, h));
                     Some((iter, triangle))
                 });
 
@@ -315,34 +773,50 @@ impl ApplicationHandler for App {
                             && frag.position.y > 0.0
                             && frag.position.y < h as f32
                         {
-                            let _color = frag.coefs.interpolate((
-                                triangle.0.color,
-                                triangle.1.color,
-                                triangle.2.color,
-                            ));
-
-                            let uvs = frag.coefs.interpolate((
-                                triangle.0.uv,
-                                triangle.1.uv,
-                                triangle.2.uv,
-                            ));
+                            let vertex = TexturedVertex::blend(triangle, frag.coefs);
 
                             let texture = Vector2::new(
-                                uvs.x * self.image.width() as f32,
-                                uvs.y * self.image.height() as f32,
+                                vertex.uv.x * self.image.width() as f32,
+                                vertex.uv.y * self.image.height() as f32,
                             );
 
-                            let Some(color) = self
+                            let Some(texel) = self
                                 .image
                                 .get_pixel_checked(texture.x as u32, texture.y as u32)
                             else {
                                 continue;
                             };
 
-                            let final_color = 0xFF000000u32
-                                + color.0[2] as u32
-                                + ((color.0[1] as u32) << 8)
-                                + ((color.0[0] as u32) << 16);
+                            let texel = Vector3::new(
+                                texel.0[0] as f32 / 255.0,
+                                texel.0[1] as f32 / 255.0,
+                                texel.0[2] as f32 / 255.0,
+                            );
+
+                            let color = if self.gamma_correct {
+                                let srgb = |vertex: TexturedVertex| {
+                                    Color::from_rgb(vertex.color.x, vertex.color.y, vertex.color.z)
+                                };
+
+                                let color = Color::interpolate_srgb(
+                                    frag.coefs,
+                                    (srgb(triangle.0), srgb(triangle.1), srgb(triangle.2)),
+                                );
+
+                                Vector3::new(color.r, color.g, color.b)
+                            } else {
+                                vertex.color
+                            };
+
+                            let shaded = self.combine.apply(color, texel);
+
+                            let color = Color::from_rgb(shaded.x, shaded.y, shaded.z);
+
+                            let final_color = match self.fog {
+                                Some(fog) => fog.apply(color, frag.w),
+                                None => color,
+                            }
+                            .to_u32();
 
                             draw.draw_pixel(
                                 frag.position.x as u32,
@@ -352,14 +826,27 @@ impl ApplicationHandler for App {
                         }
                     }
                 }
-            }),
+            }) {
+                Ok(()) | Err(PresentError::ZeroSize) => (),
+                Err(error) => {
+                    report(&error);
+                    event_loop.exit();
+                }
+            },
 
             WindowEvent::Resized(size) => {
-                state.resize(size);
+                if let Err(error) = state.resize(size) {
+                    report(&error);
+                    event_loop.exit();
+                }
                 self.w = size.width;
                 self.h = size.height;
             }
 
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                state.set_scale_factor(scale_factor);
+            }
+
             _ => (),
         }
     }
@@ -377,6 +864,9 @@ fn main() {
         h: 0,
         time: std::time::SystemTime::now(),
         image,
+        combine: Combine::Texture,
+        fog: None,
+        gamma_correct: false,
     };
     event_loop.run_app(&mut app).unwrap();
 }
@@ -384,3 +874,463 @@ fn main() {
 fn make_window(elwt: &ActiveEventLoop) -> Rc<Window> {
     Rc::new(elwt.create_window(WindowAttributes::default()).unwrap())
 }
+
+#[test]
+fn test_pixel_size_scale_factor() {
+    assert_eq!(State::pixel_size(1.0), 2);
+    assert_eq!(State::pixel_size(1.5), 3);
+    assert_eq!(State::pixel_size(2.0), 4);
+    assert_eq!(State::pixel_size(0.4), 1);
+    assert_eq!(State::pixel_size(0.1), 1);
+
+    let physical = PhysicalSize::new(1600u32, 1200u32);
+
+    assert_eq!(physical.width / State::pixel_size(2.0), 400);
+    assert_eq!(physical.height / State::pixel_size(2.0), 300);
+}
+
+#[test]
+fn test_surface_size() {
+    let (width, height) = State::surface_size(PhysicalSize::new(640, 480)).unwrap();
+    assert_eq!((width.get(), height.get()), (640, 480));
+
+    assert!(matches!(
+        State::surface_size(PhysicalSize::new(0, 480)),
+        Err(PresentError::ZeroSize)
+    ));
+    assert!(matches!(
+        State::surface_size(PhysicalSize::new(640, 0)),
+        Err(PresentError::ZeroSize)
+    ));
+}
+
+#[cfg(test)]
+#[derive(Copy, Clone, PartialEq)]
+enum MockFailure {
+    Resize,
+    BufferLock,
+    Present,
+}
+
+#[cfg(test)]
+#[derive(Debug, PartialEq)]
+enum WindowCall {
+    Title(String),
+    CursorGrab(bool),
+    Fullscreen(bool),
+}
+
+#[cfg(test)]
+#[derive(Default)]
+struct MockPresenter {
+    frames: Vec<Vec<u32>>,
+    redraws: std::cell::Cell<usize>,
+    calls: std::cell::RefCell<Vec<WindowCall>>,
+    fail: Option<MockFailure>,
+}
+
+#[cfg(test)]
+impl Presenter for MockPresenter {
+    fn resize(&mut self, _width: NonZeroU32, _height: NonZeroU32) -> Result<(), PresentError> {
+        match self.fail {
+            Some(MockFailure::Resize) => Err(PresentError::Resize("resize failed".into())),
+            _ => Ok(()),
+        }
+    }
+
+    fn present(&mut self, frame: &[u32]) -> Result<(), PresentError> {
+        match self.fail {
+            Some(MockFailure::BufferLock) => Err(PresentError::BufferLock("lock failed".into())),
+            Some(MockFailure::Present) => Err(PresentError::Present("present failed".into())),
+            _ => {
+                self.frames.push(frame.to_vec());
+                Ok(())
+            }
+        }
+    }
+
+    fn request_redraw(&self) {
+        self.redraws.set(self.redraws.get() + 1);
+    }
+
+    fn set_title(&self, title: &str) {
+        self.calls
+            .borrow_mut()
+            .push(WindowCall::Title(title.into()));
+    }
+
+    fn set_cursor_grab(&self, grab: bool) -> bool {
+        self.calls.borrow_mut().push(WindowCall::CursorGrab(grab));
+        true
+    }
+
+    fn set_fullscreen(&self, fullscreen: bool) {
+        self.calls
+            .borrow_mut()
+            .push(WindowCall::Fullscreen(fullscreen));
+    }
+}
+
+#[test]
+fn test_window_controls() {
+    let state = State::with_presenter(MockPresenter::default(), PhysicalSize::new(8, 8), 1.0);
+
+    state.set_title("software-render");
+    assert!(state.set_cursor_grab(true));
+    state.set_fullscreen(true);
+    assert!(state.set_cursor_grab(false));
+    state.set_fullscreen(false);
+
+    assert_eq!(
+        *state.presenter.calls.borrow(),
+        [
+            WindowCall::Title("software-render".into()),
+            WindowCall::CursorGrab(true),
+            WindowCall::Fullscreen(true),
+            WindowCall::CursorGrab(false),
+            WindowCall::Fullscreen(false),
+        ]
+    );
+}
+
+#[test]
+fn test_present_only() {
+    let mut state = State::with_presenter(MockPresenter::default(), PhysicalSize::new(8, 8), 1.0);
+
+    state
+        .present_only(|draw| {
+            draw.draw_pixel(1, 2, 0xff00ff);
+        })
+        .unwrap();
+
+    assert_eq!(state.presenter.redraws.get(), 0);
+    assert_eq!(state.presenter.frames.len(), 1);
+    assert_eq!(state.presenter.frames[0][4 * 8 + 2], 0xff00ff);
+
+    state
+        .present_only(|draw| {
+            draw.draw_pixel(2, 0, 0x00ffff);
+        })
+        .unwrap();
+
+    assert_eq!(state.presenter.redraws.get(), 0);
+    assert_eq!(state.presenter.frames.len(), 2);
+    assert_eq!(state.presenter.frames[1][4], 0x00ffff);
+
+    state.draw(|_| ()).unwrap();
+
+    assert_eq!(state.presenter.redraws.get(), 1);
+    assert_eq!(state.presenter.frames.len(), 3);
+}
+
+#[test]
+fn test_capture() {
+    let mut state = State::with_presenter(MockPresenter::default(), PhysicalSize::new(8, 8), 1.0);
+
+    state
+        .draw(|draw| {
+            draw.draw_pixel(0, 0, 0xff0000);
+            draw.draw_pixel(2, 1, 0x00ff00);
+        })
+        .unwrap();
+
+    let capture = state.capture();
+    assert_eq!(capture, state.presenter.frames[0]);
+    assert_eq!(capture[0], 0xff0000);
+    assert_eq!(capture[2 * 8 + 4], 0x00ff00);
+}
+
+#[test]
+fn test_present_errors() {
+    let mut state = State::with_presenter(MockPresenter::default(), PhysicalSize::new(8, 8), 1.0);
+
+    state.resize(PhysicalSize::new(0, 8)).unwrap();
+    assert!(matches!(state.draw(|_| ()), Err(PresentError::ZeroSize)));
+    assert!(matches!(
+        state.present_only(|_| ()),
+        Err(PresentError::ZeroSize)
+    ));
+    assert_eq!(state.presenter.redraws.get(), 0);
+
+    state.resize(PhysicalSize::new(8, 8)).unwrap();
+    assert_eq!(state.presenter.redraws.get(), 1);
+
+    state.presenter.fail = Some(MockFailure::BufferLock);
+    let error = state.draw(|_| ()).unwrap_err();
+    assert!(matches!(error, PresentError::BufferLock(_)));
+    assert_eq!(error.source().unwrap().to_string(), "lock failed");
+
+    state.presenter.fail = Some(MockFailure::Present);
+    assert!(matches!(
+        state.present_only(|_| ()),
+        Err(PresentError::Present(_))
+    ));
+
+    state.presenter.fail = Some(MockFailure::Resize);
+    let error = state.resize(PhysicalSize::new(16, 16)).unwrap_err();
+    assert!(matches!(error, PresentError::Resize(_)));
+    assert_eq!(error.to_string(), "failed to resize the surface");
+    assert!(state.presenter.frames.is_empty());
+}
+
+#[test]
+fn test_pixel_aspect() {
+    assert_eq!(State::pixel_extent(1.0, 1.0), PhysicalSize::new(2, 2));
+    assert_eq!(State::pixel_extent(1.0, 2.0), PhysicalSize::new(4, 2));
+    assert_eq!(State::pixel_extent(1.0, 0.5), PhysicalSize::new(2, 4));
+    assert_eq!(State::pixel_extent(2.0, 1.5), PhysicalSize::new(6, 4));
+
+    let framebuffer_width = 100;
+    let square = State::pixel_extent(1.0, 1.0);
+    let wide = State::pixel_extent(1.0, 2.0);
+
+    assert_eq!(
+        framebuffer_width * wide.width,
+        2 * framebuffer_width * square.width
+    );
+    assert_eq!(wide.height, square.height);
+
+    for invalid in [0.0, -2.0, f32::NAN, f32::INFINITY] {
+        assert_eq!(State::pixel_extent(1.0, invalid), square);
+
+        let mut state =
+            State::with_presenter(MockPresenter::default(), PhysicalSize::new(8, 8), 1.0);
+        assert!(!state.set_pixel_aspect(invalid));
+        assert_eq!(state.pixel_aspect, 1.0);
+    }
+
+    let extent = State::pixel_extent(1.0, 1e-9);
+    assert!(extent.width >= 1 && extent.height >= 1);
+
+    let mut state = State::with_presenter(MockPresenter::default(), PhysicalSize::new(8, 8), 1.0);
+    assert!(state.set_pixel_aspect(1e-12));
+    state
+        .draw(|draw| assert!(!draw.draw_pixel(1, 1, 0xffffff)))
+        .unwrap();
+}
+
+#[test]
+fn test_color_arithmetic() {
+    let sum = Color::from_rgb(0.2, 0.0, 0.0) + Color::from_rgb(0.0, 0.3, 0.0);
+    assert_eq!(sum, Color::from_rgb(0.2, 0.3, 0.0));
+
+    let difference = Color::from_rgb(0.5, 0.5, 0.5) - Color::from_rgb(0.25, 0.5, 0.0);
+    assert_eq!(difference, Color::from_rgb(0.25, 0.0, 0.5));
+
+    let color = Color {
+        r: 0.8,
+        g: 0.4,
+        b: 0.2,
+        a: 0.5,
+    };
+
+    assert_eq!(
+        color * 0.5,
+        Color {
+            r: 0.4,
+            g: 0.2,
+            b: 0.1,
+            a: 0.5,
+        }
+    );
+    assert_eq!(
+        color * Color::from_rgb(0.5, 1.0, 0.0),
+        Color {
+            r: 0.4,
+            g: 0.4,
+            b: 0.0,
+            a: 0.5,
+        }
+    );
+}
+
+#[test]
+fn test_color_adjustments() {
+    let color = Color {
+        r: 0.75,
+        g: 0.5,
+        b: 0.25,
+        a: 0.5,
+    };
+
+    let gray = color.adjust_saturation(0.0);
+    assert_eq!(gray.r, color.luma());
+    assert_eq!(gray.g, color.luma());
+    assert_eq!(gray.b, color.luma());
+    assert_eq!(gray.a, color.a);
+
+    let same = color.adjust_saturation(1.0);
+    assert!((same.r - color.r).abs() < 1e-6);
+    assert!((same.g - color.g).abs() < 1e-6);
+    assert!((same.b - color.b).abs() < 1e-6);
+
+    assert_eq!(color.adjust_contrast(1.0), color);
+    assert_eq!(
+        color.adjust_contrast(2.0),
+        Color {
+            r: 1.0,
+            g: 0.5,
+            b: 0.0,
+            a: 0.5,
+        }
+    );
+    assert_eq!(
+        color.adjust_contrast(0.0),
+        Color {
+            r: 0.5,
+            g: 0.5,
+            b: 0.5,
+            a: 0.5,
+        }
+    );
+
+    assert_eq!(
+        color.adjust_brightness(0.25),
+        Color {
+            r: 1.0,
+            g: 0.75,
+            b: 0.5,
+            a: 0.5,
+        }
+    );
+}
+
+#[test]
+fn test_color_vector4_conversion() {
+    let color = Color {
+        r: 0.1,
+        g: 0.2,
+        b: 0.3,
+        a: 0.4,
+    };
+
+    let vector: Vector4<f32> = color.into();
+    assert_eq!(vector, Vector4::new(0.1, 0.2, 0.3, 0.4));
+    assert_eq!(Color::from(vector), color);
+}
+
+#[test]
+fn test_color_srgb_interpolation() {
+    let black = Color::from_rgb(0.0, 0.0, 0.0);
+    let white = Color::from_rgb(1.0, 1.0, 1.0);
+    let coefs = Vector3::new(0.5, 0.5, 0.0);
+
+    let naive = coefs.interpolate((black, white, white));
+    assert_eq!(naive.r, 0.5);
+
+    let correct = Color::interpolate_srgb(coefs, (black, white, white));
+    assert!((correct.r - 0.7354).abs() < 1e-3);
+    assert_eq!(correct.r, correct.g);
+    assert_eq!(correct.a, 1.0);
+
+    let color = Color::from_rgb(0.2, 0.5, 0.9);
+    let round_trip = color.to_linear().to_srgb();
+    assert!((round_trip.r - color.r).abs() < 1e-5);
+    assert!((round_trip.g - color.g).abs() < 1e-5);
+    assert!((round_trip.b - color.b).abs() < 1e-5);
+}
+
+#[test]
+fn test_fog() {
+    let fog = Fog {
+        color: Color::from_rgb(0.5, 0.5, 0.5),
+        near: 0.25,
+        far: 0.75,
+    };
+    let color = Color::from_rgb(1.0, 0.0, 0.25);
+
+    assert_eq!(fog.apply(color, 0.25), color);
+    assert_eq!(fog.apply(color, 0.75), fog.color);
+    assert_eq!(fog.apply(color, 0.5), Color::from_rgb(0.75, 0.25, 0.375));
+
+    assert_eq!(fog.apply(color, 0.0), color);
+    assert_eq!(fog.apply(color, 1.0), fog.color);
+
+    let amount = |color: Color| (color.r - 1.0).abs();
+    assert!(amount(fog.apply(color, 0.7)) > amount(fog.apply(color, 0.3)));
+
+    let flat = Fog { far: 0.25, ..fog };
+    assert_eq!(flat.apply(color, 0.0), color);
+    assert_eq!(flat.apply(color, 0.25), fog.color);
+    assert_eq!(flat.apply(color, 1.0), fog.color);
+}
+
+#[test]
+fn test_render_context_depth_func() {
+    let passes = |depth_func: DepthFunc| {
+        let mut context = RenderContext::new(3, 1);
+        context.clean(0);
+        context.depthbuffer.fill(128);
+        context.set_depth_func(depth_func);
+
+        let written = [64, 128, 192]
+            .iter()
+            .enumerate()
+            .map(|(x, &z)| context.draw(x as u32, 0, z, 0xFFFFFFFF))
+            .collect::<Vec<_>>();
+
+        let colors = context.framebuffer().iter().map(|&color| color != 0);
+        assert!(colors.eq(written.iter().copied()));
+
+        written
+    };
+
+    assert_eq!(passes(DepthFunc::LessEqual), [true, true, false]);
+    assert_eq!(passes(DepthFunc::Greater), [false, false, true]);
+    assert_eq!(passes(DepthFunc::Always), [true, true, true]);
+    assert_eq!(passes(DepthFunc::Never), [false, false, false]);
+}
+
+#[test]
+fn test_hidden_line() {
+    let mut context = RenderContext::new(20, 20);
+    context.clean(0);
+
+    let far = [
+        Vector4::new(2.0, 2.0, 0.8, 1.0),
+        Vector4::new(17.0, 2.0, 0.8, 1.0),
+        Vector4::new(2.0, 17.0, 0.8, 1.0),
+    ];
+    let near = [
+        Vector4::new(5.0, 0.0, 0.2, 1.0),
+        Vector4::new(15.0, 0.0, 0.2, 1.0),
+        Vector4::new(5.0, 10.0, 0.2, 1.0),
+    ];
+
+    let (fill, edge) = (0xFF202020, 0xFFFFFFFF);
+    context.draw_hidden_line(&[far, near], fill, edge);
+
+    let pixel = |x: u32, y: u32| context.framebuffer()[(y * 20 + x) as usize];
+
+    assert_eq!(pixel(16, 2), edge);
+    assert_eq!(pixel(2, 10), edge);
+    assert_eq!(pixel(10, 0), edge);
+
+    assert_eq!(pixel(10, 2), fill);
+    assert_eq!(pixel(8, 2), fill);
+    assert_eq!(pixel(4, 12), fill);
+
+    assert_eq!(context.depth_func, DepthFunc::LessEqual);
+}
+
+#[test]
+fn test_hidden_line_far_vertex() {
+    let mut context = RenderContext::new(20, 20);
+    context.clean(0);
+
+    let triangle = [
+        Vector4::new(2.0, 2.0, 0.5, 1.0),
+        Vector4::new(2e8, 2.0, 0.5, 1.0),
+        Vector4::new(2.0, 17.0, 0.5, 1.0),
+    ];
+
+    let (fill, edge) = (0xFF202020, 0xFFFFFFFF);
+    context.draw_hidden_line(&[triangle], fill, edge);
+
+    let pixel = |x: u32, y: u32| context.framebuffer()[(y * 20 + x) as usize];
+
+    assert_eq!(pixel(10, 2), edge);
+    assert_eq!(pixel(19, 2), edge);
+    assert_eq!(pixel(2, 10), edge);
+    assert_eq!(pixel(10, 10), fill);
+}
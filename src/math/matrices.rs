@@ -1,9 +1,11 @@
 use std::ops::Mul;
 
 use super::vectors::Vector4;
-use super::vectors::{Number, Vector2};
+use super::vectors::{Number, Vector2, Vector3};
 
-#[derive(Copy, Clone, Debug)]
+/// Row-major 4x4 matrix: `x`, `y`, `z` and `w` are the rows. Vectors are
+/// columns, so `matrix * vector` dots every row with the vector.
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Matrix4<T: Number<T>> {
     pub x: Vector4<T>,
     pub y: Vector4<T>,
@@ -48,7 +50,12 @@ impl<T: Number<T>> Mul<Vector4<T>> for Matrix4<T> {
     type Output = Vector4<T>;
 
     fn mul(self, rhs: Vector4<T>) -> Self::Output {
-        Vector4::new(self.x * rhs, self.y * rhs, self.z * rhs, self.w * rhs)
+        Vector4::new(
+            self.x.dot(rhs),
+            self.y.dot(rhs),
+            self.z.dot(rhs),
+            self.w.dot(rhs),
+        )
     }
 }
 
@@ -57,6 +64,61 @@ impl<T: Number<T>> Matrix4<T> {
         Self { x, y, z, w }
     }
 
+    #[allow(dead_code)]
+    pub fn from_rows(x: Vector4<T>, y: Vector4<T>, z: Vector4<T>, w: Vector4<T>) -> Self {
+        Self::new(x, y, z, w)
+    }
+
+    #[allow(dead_code)]
+    pub fn from_cols(x: Vector4<T>, y: Vector4<T>, z: Vector4<T>, w: Vector4<T>) -> Self {
+        Self::new(
+            Vector4::new(x.x, y.x, z.x, w.x),
+            Vector4::new(x.y, y.y, z.y, w.y),
+            Vector4::new(x.z, y.z, z.z, w.z),
+            Vector4::new(x.w, y.w, z.w, w.w),
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn row(&self, i: usize) -> Vector4<T> {
+        match i {
+            0 => self.x,
+            1 => self.y,
+            2 => self.z,
+            3 => self.w,
+            _ => panic!("Matrix4 row index out of range: {i}"),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn col(&self, i: usize) -> Vector4<T> {
+        let pick = |row: Vector4<T>| match i {
+            0 => row.x,
+            1 => row.y,
+            2 => row.z,
+            3 => row.w,
+            _ => panic!("Matrix4 column index out of range: {i}"),
+        };
+
+        Vector4::new(pick(self.x), pick(self.y), pick(self.z), pick(self.w))
+    }
+
+    #[allow(dead_code)]
+    pub fn determinant(self) -> T {
+        let rows = [self.x, self.y, self.z, self.w].map(|row| [row.x, row.y, row.z, row.w]);
+
+        let minor = |column: usize| {
+            let [i, j, k] = [[1, 2, 3], [0, 2, 3], [0, 1, 3], [0, 1, 2]][column];
+            let [a, b, c] = [rows[1], rows[2], rows[3]];
+
+            a[i] * (b[j] * c[k] - b[k] * c[j]) - a[j] * (b[i] * c[k] - b[k] * c[i])
+                + a[k] * (b[i] * c[j] - b[j] * c[i])
+        };
+
+        rows[0][0] * minor(0) - rows[0][1] * minor(1) + rows[0][2] * minor(2)
+            - rows[0][3] * minor(3)
+    }
+
     pub fn identity() -> Self {
         Self::new(
             Vector4::new(T::one(), T::zero(), T::zero(), T::zero()),
@@ -68,6 +130,31 @@ impl<T: Number<T>> Matrix4<T> {
 }
 
 impl Matrix4<f32> {
+    // Divides by w unless it is zero, like From<Vector4> for Vector3.
+    #[allow(dead_code)]
+    pub fn mul_point(&self, p: Vector3<f32>) -> Vector3<f32> {
+        (*self * Vector4::new(p.x, p.y, p.z, 1.0)).into()
+    }
+
+    #[allow(dead_code)]
+    pub fn mul_direction(&self, d: Vector3<f32>) -> Vector3<f32> {
+        let v = *self * Vector4::new(d.x, d.y, d.z, 0.0);
+
+        Vector3::new(v.x, v.y, v.z)
+    }
+
+    #[allow(dead_code)]
+    pub fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        (0..4).all(|i| {
+            let (a, b) = (self.row(i), other.row(i));
+
+            (a.x - b.x).abs() <= epsilon
+                && (a.y - b.y).abs() <= epsilon
+                && (a.z - b.z).abs() <= epsilon
+                && (a.w - b.w).abs() <= epsilon
+        })
+    }
+
     pub fn projection(aspect: f32, fov: f32, z_near: f32, z_far: f32) -> Self {
         let a = 1.0 / aspect;
         let f = 1.0 / (fov / 2.0).tan();
@@ -90,16 +177,74 @@ impl Matrix4<f32> {
         )
     }
 
+    #[allow(dead_code)]
+    pub fn rotation_axis(axis: Vector3<f32>, angle: f32) -> Self {
+        let Vector3 { x, y, z } = axis.normalize();
+        let (sin, cos) = angle.sin_cos();
+        let t = 1.0 - cos;
+
+        Self::new(
+            Vector4::new(
+                t * x * x + cos,
+                t * x * y - sin * z,
+                t * x * z + sin * y,
+                0.0,
+            ),
+            Vector4::new(
+                t * x * y + sin * z,
+                t * y * y + cos,
+                t * y * z - sin * x,
+                0.0,
+            ),
+            Vector4::new(
+                t * x * z - sin * y,
+                t * y * z + sin * x,
+                t * z * z + cos,
+                0.0,
+            ),
+            Vector4::new(0.0, 0.0, 0.0, 1.0),
+        )
+    }
+
     pub fn viewport(size: Vector2<i32>) -> Self {
+        Self::viewport_rect(&Viewport::new(Vector2::new(0, 0), size))
+    }
+
+    pub fn viewport_rect(viewport: &Viewport) -> Self {
+        let origin = viewport.origin;
+        let size = viewport.size;
+
         Self::new(
-            Vector4::new(size.x as f32 / 2.0, 0.0, 0.0, size.x as f32 / 2.0),
-            Vector4::new(0.0, -size.y as f32 / 2.0, 0.0, size.y as f32 / 2.0),
+            Vector4::new(
+                size.x as f32 / 2.0,
+                0.0,
+                0.0,
+                origin.x as f32 + size.x as f32 / 2.0,
+            ),
+            Vector4::new(
+                0.0,
+                -size.y as f32 / 2.0,
+                0.0,
+                origin.y as f32 + size.y as f32 / 2.0,
+            ),
             Vector4::new(0.0, 0.0, 0.5, 0.5),
             Vector4::new(0.0, 0.0, 0.0, 1.0),
         )
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Viewport {
+    pub origin: Vector2<i32>,
+    pub size: Vector2<i32>,
+}
+
+impl Viewport {
+    pub fn new(origin: Vector2<i32>, size: Vector2<i32>) -> Self {
+        Self { origin, size }
+    }
+}
+
 #[test]
 fn test_polygon() {
     let projection = Matrix4::<f32>::projection(1.0, 3.14 / 2.0, 0.1, 100.0);
@@ -113,3 +258,179 @@ fn test_polygon() {
 
     assert_eq!(first, second);
 }
+
+#[test]
+fn test_rotation_axis() {
+    use std::f32::consts::PI;
+
+    let near = |a: Matrix4<f32>, b: Matrix4<f32>| {
+        [(a.x, b.x), (a.y, b.y), (a.z, b.z), (a.w, b.w)]
+            .into_iter()
+            .all(|(a, b)| {
+                (a.x - b.x).abs() < 1e-5
+                    && (a.y - b.y).abs() < 1e-5
+                    && (a.z - b.z).abs() < 1e-5
+                    && (a.w - b.w).abs() < 1e-5
+            })
+    };
+
+    let rotation_y = Matrix4::new(
+        Vector4::new(0.0, 0.0, 1.0, 0.0),
+        Vector4::new(0.0, 1.0, 0.0, 0.0),
+        Vector4::new(-1.0, 0.0, 0.0, 0.0),
+        Vector4::new(0.0, 0.0, 0.0, 1.0),
+    );
+
+    assert!(near(
+        Matrix4::rotation_axis(Vector3::new(0.0, 1.0, 0.0), PI / 2.0),
+        rotation_y
+    ));
+    assert!(near(
+        Matrix4::rotation_axis(Vector3::new(3.0, 0.0, 0.0), 0.7),
+        Matrix4::rotation_x(0.7)
+    ));
+    assert!(near(
+        Matrix4::rotation_axis(Vector3::new(1.0, 1.0, 1.0), 2.0 * PI),
+        Matrix4::identity()
+    ));
+}
+
+#[test]
+fn test_viewport_rect() {
+    let size = Vector2::new(200, 100);
+    let origin = Vector2::new(40, 30);
+
+    let full = Matrix4::viewport(size);
+    let offset = Matrix4::viewport_rect(&Viewport::new(origin, size));
+
+    for point in [
+        Vector4::new(0.0, 0.0, 0.0, 1.0),
+        Vector4::new(-1.0, 1.0, 0.5, 1.0),
+        Vector4::new(0.5, -0.25, 1.0, 1.0),
+    ] {
+        let expected = full * point;
+        let actual = offset * point;
+
+        assert_eq!(actual.x, expected.x + origin.x as f32);
+        assert_eq!(actual.y, expected.y + origin.y as f32);
+        assert_eq!(actual.z, expected.z);
+    }
+
+    assert_eq!(
+        offset * Vector4::new(-1.0, 1.0, 0.0, 1.0),
+        Vector4::new(40.0, 30.0, 0.5, 1.0)
+    );
+}
+
+#[test]
+fn test_rows_and_cols() {
+    let matrix = Matrix4::new(
+        Vector4::new(1, 2, 3, 4),
+        Vector4::new(5, 6, 7, 8),
+        Vector4::new(9, 10, 11, 12),
+        Vector4::new(13, 14, 15, 16),
+    );
+
+    assert_eq!(matrix.row(1), Vector4::new(5, 6, 7, 8));
+    assert_eq!(matrix.col(0), Vector4::new(1, 5, 9, 13));
+    assert_eq!(matrix.col(3), Vector4::new(4, 8, 12, 16));
+
+    let transposed = Matrix4::from_cols(matrix.x, matrix.y, matrix.z, matrix.w);
+
+    for i in 0..4 {
+        assert_eq!(transposed.col(i), matrix.row(i));
+        assert_eq!(transposed.row(i), matrix.col(i));
+    }
+
+    let rebuilt = Matrix4::from_cols(matrix.col(0), matrix.col(1), matrix.col(2), matrix.col(3));
+
+    for i in 0..4 {
+        assert_eq!(rebuilt.row(i), matrix.row(i));
+    }
+}
+
+#[test]
+fn test_translation_column() {
+    let mut look = Matrix4::<f32>::identity();
+    look.z.w = -1.5;
+
+    assert_eq!(look.col(3), Vector4::new(0.0, 0.0, -1.5, 1.0));
+    assert_eq!(look * Vector4::new(0.0, 0.0, 0.0, 1.0), look.col(3));
+}
+
+#[test]
+fn test_determinant() {
+    assert_eq!(Matrix4::<f32>::identity().determinant(), 1.0);
+    assert_eq!(Matrix4::<i32>::identity().determinant(), 1);
+
+    let scale = Matrix4::new(
+        Vector4::new(2.0, 0.0, 0.0, 0.0),
+        Vector4::new(0.0, 3.0, 0.0, 0.0),
+        Vector4::new(0.0, 0.0, 4.0, 0.0),
+        Vector4::new(0.0, 0.0, 0.0, 1.0),
+    );
+    assert_eq!(scale.determinant(), 24.0);
+
+    let repeated = Matrix4::new(
+        Vector4::new(1, 2, 3, 4),
+        Vector4::new(5, 6, 7, 8),
+        Vector4::new(1, 2, 3, 4),
+        Vector4::new(3, 1, 1, 2),
+    );
+    assert_eq!(repeated.determinant(), 0);
+
+    let general = Matrix4::new(
+        Vector4::new(1, 2, 3, 4),
+        Vector4::new(5, 6, 7, 8),
+        Vector4::new(2, 6, 4, 8),
+        Vector4::new(3, 1, 1, 2),
+    );
+    assert_eq!(general.determinant(), 72);
+}
+
+#[test]
+fn test_matrix_equality() {
+    assert_eq!(Matrix4::<f32>::identity(), Matrix4::identity());
+    assert_ne!(Matrix4::<f32>::identity(), Matrix4::rotation_x(0.5));
+
+    let mut nudged = Matrix4::<f32>::identity();
+    nudged.x.x += 1e-7;
+    nudged.z.w -= 1e-7;
+
+    assert_ne!(nudged, Matrix4::identity());
+    assert!(nudged.approx_eq(&Matrix4::identity(), 1e-6));
+    assert!(!Matrix4::rotation_x(0.5).approx_eq(&Matrix4::identity(), 1e-6));
+}
+
+#[test]
+fn test_mul_point_and_direction() {
+    let mut translation = Matrix4::<f32>::identity();
+    translation.x.w = 10.0;
+    translation.y.w = -2.0;
+
+    let v = Vector3::new(1.0, 2.0, 3.0);
+
+    assert_eq!(translation.mul_point(v), Vector3::new(11.0, 0.0, 3.0));
+    assert_eq!(translation.mul_direction(v), v);
+
+    let mut scaled_w = translation;
+    scaled_w.w.w = 2.0;
+
+    assert_eq!(scaled_w.mul_point(v), Vector3::new(5.5, 0.0, 1.5));
+    assert_eq!(scaled_w.mul_direction(v), v);
+
+    let projection = Matrix4::projection(2.0, std::f32::consts::FRAC_PI_2, 1.0, 2.0);
+
+    assert_eq!(
+        projection.mul_direction(Vector3::new(0.0, 0.0, 1.0)),
+        Vector3::new(0.0, 0.0, 2.0)
+    );
+    assert_eq!(
+        projection.mul_point(Vector3::new(0.0, 0.0, -2.0)),
+        Vector3::new(0.0, 0.0, -1.0)
+    );
+    assert_eq!(
+        projection.mul_point(Vector3::new(0.0, 0.0, 0.0)),
+        Vector3::new(0.0, 0.0, 2.0)
+    );
+}
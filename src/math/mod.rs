@@ -1,2 +1,3 @@
 pub mod matrices;
+pub mod plane;
 pub mod vectors;
@@ -0,0 +1,80 @@
+use super::vectors::Vector3;
+
+const PARALLEL_EPSILON: f32 = 1e-6;
+
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Plane {
+    pub normal: Vector3<f32>,
+    pub d: f32,
+}
+
+#[allow(dead_code)]
+impl Plane {
+    pub fn from_point_normal(point: Vector3<f32>, normal: Vector3<f32>) -> Self {
+        let normal = normal.normalize();
+
+        Self {
+            normal,
+            d: -normal.dot(point),
+        }
+    }
+
+    pub fn distance(&self, point: Vector3<f32>) -> f32 {
+        self.normal.dot(point) + self.d
+    }
+
+    pub fn intersect_ray(&self, origin: Vector3<f32>, dir: Vector3<f32>) -> Option<f32> {
+        let denominator = self.normal.dot(dir);
+
+        if denominator.abs() < PARALLEL_EPSILON {
+            return None;
+        }
+
+        let t = -self.distance(origin) / denominator;
+
+        if t < 0.0 {
+            return None;
+        }
+
+        Some(t)
+    }
+}
+
+#[test]
+fn test_plane_distance() {
+    let plane = Plane::from_point_normal(Vector3::new(0.0, 2.0, 0.0), Vector3::new(0.0, 3.0, 0.0));
+
+    assert_eq!(plane.distance(Vector3::new(4.0, 5.0, -1.0)), 3.0);
+    assert_eq!(plane.distance(Vector3::new(0.0, 0.0, 0.0)), -2.0);
+}
+
+#[test]
+fn test_plane_intersect_ray() {
+    let ground = Plane::from_point_normal(Vector3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+
+    let origin = Vector3::new(1.0, 5.0, 2.0);
+
+    assert_eq!(
+        ground.intersect_ray(origin, Vector3::new(0.0, -1.0, 0.0)),
+        Some(5.0)
+    );
+    assert_eq!(
+        ground.intersect_ray(origin, Vector3::new(0.0, -2.0, 0.0)),
+        Some(2.5)
+    );
+    assert_eq!(
+        ground.intersect_ray(origin, Vector3::new(0.0, 1.0, 0.0)),
+        None
+    );
+}
+
+#[test]
+fn test_plane_parallel_ray() {
+    let ground = Plane::from_point_normal(Vector3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+
+    assert_eq!(
+        ground.intersect_ray(Vector3::new(0.0, 5.0, 0.0), Vector3::new(1.0, 0.0, 0.0)),
+        None
+    );
+}
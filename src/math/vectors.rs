@@ -1,6 +1,9 @@
 use std::fmt::Debug;
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
+// Careful: `a * b` between two vectors is the dot product and returns a
+// scalar, not a component-wise product. `a * s` with a scalar scales.
+
 pub trait Number<T>: Copy + Clone + Debug + num::Num + Neg<Output = T> {}
 
 impl Number<f32> for f32 {}
@@ -8,6 +11,22 @@ impl Number<f64> for f64 {}
 
 impl Number<i32> for i32 {}
 
+fn min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Vector2<T: Number<T>> {
     pub x: T,
@@ -18,6 +37,48 @@ impl<T: Number<T>> Vector2<T> {
     pub fn new(x: T, y: T) -> Self {
         Self { x, y }
     }
+
+    pub fn dot(self, rhs: Self) -> T {
+        self.x * rhs.x + self.y * rhs.y
+    }
+}
+
+#[allow(dead_code)]
+impl Vector2<f32> {
+    pub fn distance(self, other: Self) -> f32 {
+        self.distance_squared(other).sqrt()
+    }
+
+    pub fn distance_squared(self, other: Self) -> f32 {
+        let delta = self - other;
+        delta.dot(delta)
+    }
+}
+
+#[allow(dead_code)]
+impl Vector2<i32> {
+    pub fn manhattan_distance(self, other: Self) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn chebyshev_distance(self, other: Self) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+}
+
+#[allow(dead_code)]
+impl<T: Number<T> + PartialOrd> Vector2<T> {
+    pub fn min(self, other: Self) -> Self {
+        Self::new(min(self.x, other.x), min(self.y, other.y))
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        Self::new(max(self.x, other.x), max(self.y, other.y))
+    }
+
+    pub fn clamp(self, lo: Self, hi: Self) -> Self {
+        self.max(lo).min(hi)
+    }
 }
 
 impl<T: Number<T>> Add for Vector2<T> {
@@ -48,7 +109,7 @@ impl<T: Number<T>> Mul for Vector2<T> {
     type Output = T;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        self.x * rhs.x + self.y * rhs.y
+        self.dot(rhs)
     }
 }
 
@@ -80,6 +141,10 @@ impl<T: Number<T>> Vector3<T> {
         Self { x, y, z }
     }
 
+    pub fn dot(self, rhs: Self) -> T {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+
     pub fn cross(self, rhs: Self) -> Self {
         Self::new(
             self.y * rhs.z - self.z * rhs.y,
@@ -96,6 +161,67 @@ impl<T: Number<T>> Vector3<T> {
     }
 }
 
+impl Vector3<f32> {
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(self) -> Self {
+        self / self.length()
+    }
+
+    #[allow(dead_code)]
+    pub fn distance(self, other: Self) -> f32 {
+        (self - other).length()
+    }
+
+    // Weights of `p` with respect to `a`, `b`, `c` from the signed areas of
+    // the sub-triangles opposite each vertex.
+    #[allow(dead_code)]
+    pub fn barycentric(a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>, p: Vector2<f32>) -> Self {
+        let area = |a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>| {
+            (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+        };
+
+        let total = area(a, b, c);
+
+        Self::new(
+            area(p, b, c) / total,
+            area(a, p, c) / total,
+            area(a, b, p) / total,
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn distance_squared(self, other: Self) -> f32 {
+        let delta = self - other;
+        delta.dot(delta)
+    }
+}
+
+#[allow(dead_code)]
+impl<T: Number<T> + PartialOrd> Vector3<T> {
+    pub fn min(self, other: Self) -> Self {
+        Self::new(
+            min(self.x, other.x),
+            min(self.y, other.y),
+            min(self.z, other.z),
+        )
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        Self::new(
+            max(self.x, other.x),
+            max(self.y, other.y),
+            max(self.z, other.z),
+        )
+    }
+
+    pub fn clamp(self, lo: Self, hi: Self) -> Self {
+        self.max(lo).min(hi)
+    }
+}
+
 impl<T: Number<T>> Add for Vector3<T> {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
@@ -121,7 +247,7 @@ impl<T: Number<T>> Mul for Vector3<T> {
     type Output = T;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+        self.dot(rhs)
     }
 }
 
@@ -153,13 +279,63 @@ impl<T: Number<T>> Vector4<T> {
     pub fn new(x: T, y: T, z: T, w: T) -> Self {
         Self { x, y, z, w }
     }
+
+    pub fn dot(self, rhs: Self) -> T {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z + self.w * rhs.w
+    }
+}
+
+#[allow(dead_code)]
+impl<T: Number<T> + PartialOrd> Vector4<T> {
+    pub fn min(self, other: Self) -> Self {
+        Self::new(
+            min(self.x, other.x),
+            min(self.y, other.y),
+            min(self.z, other.z),
+            min(self.w, other.w),
+        )
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        Self::new(
+            max(self.x, other.x),
+            max(self.y, other.y),
+            max(self.z, other.z),
+            max(self.w, other.w),
+        )
+    }
+
+    pub fn clamp(self, lo: Self, hi: Self) -> Self {
+        self.max(lo).min(hi)
+    }
 }
 
 impl<T: Number<T>> Mul for Vector4<T> {
     type Output = T;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z + self.w * rhs.w
+        self.dot(rhs)
+    }
+}
+
+impl<T: Number<T>> Add for Vector4<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(
+            self.x + rhs.x,
+            self.y + rhs.y,
+            self.z + rhs.z,
+            self.w + rhs.w,
+        )
+    }
+}
+
+impl<T: Number<T>> Mul<T> for Vector4<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs, self.w * rhs)
     }
 }
 
@@ -188,3 +364,160 @@ impl<T: Number<T>> From<Vector2<T>> for Vector3<T> {
         Self::new(value.x, value.y, T::zero())
     }
 }
+
+impl<T: Number<T>> From<(T, T, T)> for Vector3<T> {
+    fn from(value: (T, T, T)) -> Self {
+        Self::new(value.0, value.1, value.2)
+    }
+}
+
+impl<T: Number<T>> From<Vector3<T>> for (T, T, T) {
+    fn from(value: Vector3<T>) -> Self {
+        (value.x, value.y, value.z)
+    }
+}
+
+impl<T: Number<T>> From<(T, T, T, T)> for Vector4<T> {
+    fn from(value: (T, T, T, T)) -> Self {
+        Self::new(value.0, value.1, value.2, value.3)
+    }
+}
+
+impl<T: Number<T>> From<Vector4<T>> for (T, T, T, T) {
+    fn from(value: Vector4<T>) -> Self {
+        (value.x, value.y, value.z, value.w)
+    }
+}
+
+#[test]
+fn test_min_max() {
+    let a = Vector3::new(1, 5, 3);
+    let b = Vector3::new(4, 2, 3);
+
+    assert_eq!(a.max(b), Vector3::new(4, 5, 3));
+    assert_eq!(a.min(b), Vector3::new(1, 2, 3));
+
+    assert_eq!(
+        Vector2::new(1.5, -2.0).max(Vector2::new(0.5, 1.0)),
+        Vector2::new(1.5, 1.0)
+    );
+    assert_eq!(
+        Vector4::new(1, 2, 3, 4).min(Vector4::new(4, 3, 2, 1)),
+        Vector4::new(1, 2, 2, 1)
+    );
+}
+
+#[test]
+fn test_clamp() {
+    let lo = Vector3::new(0.0, 0.0, 0.0);
+    let hi = Vector3::new(1.0, 1.0, 1.0);
+
+    assert_eq!(
+        Vector3::new(-0.5, 0.25, 3.0).clamp(lo, hi),
+        Vector3::new(0.0, 0.25, 1.0)
+    );
+    assert_eq!(
+        Vector2::new(10, -10).clamp(Vector2::new(0, -5), Vector2::new(5, 5)),
+        Vector2::new(5, -5)
+    );
+    assert_eq!(
+        Vector4::new(2, 7, -1, 4).clamp(Vector4::new(0, 0, 0, 0), Vector4::new(3, 3, 3, 3)),
+        Vector4::new(2, 3, 0, 3)
+    );
+}
+
+#[test]
+fn test_tuple_conversions() {
+    let vector: Vector3<f32> = (1.0, 2.0, 3.0).into();
+    assert_eq!(vector, Vector3::new(1.0, 2.0, 3.0));
+
+    let tuple: (f32, f32, f32) = vector.into();
+    assert_eq!(tuple, (1.0, 2.0, 3.0));
+
+    let vector: Vector4<i32> = (1, 2, 3, 4).into();
+    assert_eq!(vector, Vector4::new(1, 2, 3, 4));
+
+    let tuple: (i32, i32, i32, i32) = vector.into();
+    assert_eq!(tuple, (1, 2, 3, 4));
+}
+
+#[test]
+fn test_distance() {
+    let a = Vector2::new(0.0, 0.0);
+    let b = Vector2::new(3.0, 4.0);
+
+    assert_eq!(a.distance(b), 5.0);
+    assert_eq!(a.distance_squared(b), 25.0);
+    assert_eq!(b.distance(a), 5.0);
+
+    let a = Vector3::new(1.0, 1.0, 1.0);
+    let b = Vector3::new(3.0, 4.0, 7.0);
+
+    assert_eq!(a.distance(b), 7.0);
+    assert_eq!(a.distance_squared(b), 49.0);
+}
+
+#[test]
+fn test_dot() {
+    let a = Vector2::new(1.0, 2.0);
+    let b = Vector2::new(3.0, -4.0);
+    assert_eq!(a.dot(b), a * b);
+    assert_eq!(a.dot(b), -5.0);
+
+    let a = Vector3::new(1, 2, 3);
+    let b = Vector3::new(4, 5, 6);
+    assert_eq!(a.dot(b), a * b);
+    assert_eq!(a.dot(b), 32);
+    assert_eq!(a.dot(a.cross(b)), 0);
+
+    let a = Vector4::new(1.0, 2.0, 3.0, 4.0);
+    let b = Vector4::new(0.5, 0.5, 0.5, 0.5);
+    assert_eq!(a.dot(b), a * b);
+    assert_eq!(a.dot(b), 5.0);
+}
+
+#[test]
+fn test_barycentric() {
+    let a = Vector2::new(0.0, 0.0);
+    let b = Vector2::new(4.0, 0.0);
+    let c = Vector2::new(0.0, 4.0);
+
+    assert_eq!(
+        Vector3::barycentric(a, b, c, a),
+        Vector3::new(1.0, 0.0, 0.0)
+    );
+    assert_eq!(
+        Vector3::barycentric(a, b, c, b),
+        Vector3::new(0.0, 1.0, 0.0)
+    );
+    assert_eq!(
+        Vector3::barycentric(a, b, c, c),
+        Vector3::new(0.0, 0.0, 1.0)
+    );
+
+    assert_eq!(
+        Vector3::barycentric(a, b, c, Vector2::new(2.0, 1.0)),
+        Vector3::new(0.25, 0.5, 0.25)
+    );
+    assert_eq!(
+        Vector3::barycentric(c, b, a, Vector2::new(2.0, 1.0)),
+        Vector3::new(0.25, 0.5, 0.25)
+    );
+}
+
+#[test]
+fn test_grid_distances() {
+    let origin = Vector2::new(0, 0);
+    let point = Vector2::new(3, 4);
+
+    assert_eq!(origin.manhattan_distance(point), 7);
+    assert_eq!(origin.chebyshev_distance(point), 4);
+
+    let a = Vector2::new(-2, 5);
+    let b = Vector2::new(3, -1);
+
+    assert_eq!(a.manhattan_distance(b), 11);
+    assert_eq!(b.manhattan_distance(a), 11);
+    assert_eq!(a.chebyshev_distance(b), 6);
+    assert_eq!(a.chebyshev_distance(a), 0);
+}
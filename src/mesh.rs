@@ -0,0 +1,150 @@
+use crate::math::vectors::{Vector3, Vector4};
+
+pub trait Vertex: Copy {
+    fn position(&self) -> Vector4<f32>;
+
+    fn blend(vertices: (Self, Self, Self), coefs: Vector3<f32>) -> Self;
+}
+
+impl Vertex for Vector4<f32> {
+    fn position(&self) -> Vector4<f32> {
+        *self
+    }
+
+    fn blend(vertices: (Self, Self, Self), coefs: Vector3<f32>) -> Self {
+        coefs.interpolate(vertices)
+    }
+}
+
+pub struct Mesh<V: Vertex> {
+    pub vertices: Vec<V>,
+    pub indices: Vec<(usize, usize, usize)>,
+}
+
+impl<V: Vertex> Mesh<V> {
+    pub fn new(vertices: Vec<V>, indices: Vec<(usize, usize, usize)>) -> Self {
+        Self { vertices, indices }
+    }
+
+    pub fn triangles(&self) -> impl Iterator<Item = (V, V, V)> + '_ {
+        self.indices
+            .iter()
+            .map(|&(a, b, c)| (self.vertices[a], self.vertices[b], self.vertices[c]))
+    }
+}
+
+// Splits each triangle into four by its edge midpoints, `levels` times over,
+// keeping the winding of the original.
+#[allow(dead_code)]
+pub fn tessellate<V: Vertex>(triangle: (V, V, V), levels: u32) -> Vec<(V, V, V)> {
+    if levels == 0 {
+        return vec![triangle];
+    }
+
+    let midpoint = |coefs: Vector3<f32>| V::blend(triangle, coefs);
+
+    let (a, b, c) = triangle;
+    let ab = midpoint(Vector3::new(0.5, 0.5, 0.0));
+    let bc = midpoint(Vector3::new(0.0, 0.5, 0.5));
+    let ca = midpoint(Vector3::new(0.5, 0.0, 0.5));
+
+    [(a, ab, ca), (ab, b, bc), (ca, bc, c), (ab, bc, ca)]
+        .into_iter()
+        .flat_map(|triangle| tessellate(triangle, levels - 1))
+        .collect()
+}
+
+#[cfg(test)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct HeatVertex {
+    position: Vector4<f32>,
+    heat: f32,
+}
+
+#[cfg(test)]
+impl Vertex for HeatVertex {
+    fn position(&self) -> Vector4<f32> {
+        self.position
+    }
+
+    fn blend(vertices: (Self, Self, Self), coefs: Vector3<f32>) -> Self {
+        Self {
+            position: coefs.interpolate((
+                vertices.0.position,
+                vertices.1.position,
+                vertices.2.position,
+            )),
+            heat: coefs.interpolate((vertices.0.heat, vertices.1.heat, vertices.2.heat)),
+        }
+    }
+}
+
+#[test]
+fn test_vertex_blend_centroid() {
+    let vertex = |x: f32, y: f32, heat: f32| HeatVertex {
+        position: Vector4::new(x, y, 0.0, 1.0),
+        heat,
+    };
+
+    let mesh = Mesh::new(
+        vec![
+            vertex(0.0, 0.0, 10.0),
+            vertex(3.0, 0.0, 40.0),
+            vertex(0.0, 3.0, 70.0),
+            vertex(3.0, 3.0, 0.0),
+        ],
+        vec![(0, 1, 2), (1, 3, 2)],
+    );
+
+    let triangles: Vec<_> = mesh.triangles().collect();
+    assert_eq!(triangles.len(), 2);
+    assert_eq!(triangles[1].1, mesh.vertices[3]);
+
+    let third = 1.0 / 3.0;
+    let centroid = HeatVertex::blend(triangles[0], Vector3::new(third, third, third));
+
+    assert!((centroid.heat - 40.0).abs() < 1e-4);
+    assert!((centroid.position.x - 1.0).abs() < 1e-6);
+    assert!((centroid.position.y - 1.0).abs() < 1e-6);
+    assert!((centroid.position().w - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_tessellate() {
+    let vertex = |x: f32, y: f32, heat: f32| HeatVertex {
+        position: Vector4::new(x, y, 0.0, 1.0),
+        heat,
+    };
+
+    let area = |(a, b, c): (HeatVertex, HeatVertex, HeatVertex)| {
+        let a: Vector3<f32> = a.position.into();
+        let b: Vector3<f32> = b.position.into();
+        let c: Vector3<f32> = c.position.into();
+
+        0.5 * (b - a).cross(c - a).z
+    };
+
+    let triangle = (
+        vertex(0.0, 0.0, 10.0),
+        vertex(4.0, 0.0, 30.0),
+        vertex(0.0, 4.0, 50.0),
+    );
+
+    assert_eq!(tessellate(triangle, 0), vec![triangle]);
+
+    let once = tessellate(triangle, 1);
+    assert_eq!(once.len(), 4);
+    assert_eq!(once.iter().map(|&t| area(t)).sum::<f32>(), area(triangle));
+    assert!(once.iter().all(|&t| area(t) > 0.0));
+
+    let (_, ab, ca) = once[0];
+    let (_, _, bc) = once[1];
+
+    assert_eq!(ab, vertex(2.0, 0.0, 20.0));
+    assert_eq!(bc, vertex(2.0, 2.0, 40.0));
+    assert_eq!(ca, vertex(0.0, 2.0, 30.0));
+
+    let twice = tessellate(triangle, 2);
+    assert_eq!(twice.len(), 16);
+    assert_eq!(twice.iter().map(|&t| area(t)).sum::<f32>(), area(triangle));
+}
@@ -0,0 +1,100 @@
+use crate::math::vectors::Vector2;
+
+fn hash(x: i32, y: i32, seed: u32) -> u32 {
+    let mut h = seed ^ (x as u32).wrapping_mul(0x27d4_eb2d) ^ (y as u32).wrapping_mul(0x1656_67b1);
+
+    h = (h ^ (h >> 15)).wrapping_mul(0x2c1b_3c6d);
+    h = (h ^ (h >> 12)).wrapping_mul(0x297a_2d39);
+
+    h ^ (h >> 15)
+}
+
+fn lattice(x: i32, y: i32, seed: u32) -> f32 {
+    hash(x, y, seed) as f32 / u32::MAX as f32
+}
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Smoothly interpolated lattice noise in `[0, 1]`, fully determined by `p` and `seed`.
+#[allow(dead_code)]
+pub fn value_noise_2d(p: Vector2<f32>, seed: u32) -> f32 {
+    let x0 = p.x.floor();
+    let y0 = p.y.floor();
+
+    let tx = smoothstep(p.x - x0);
+    let ty = smoothstep(p.y - y0);
+
+    let x0 = x0 as i32;
+    let y0 = y0 as i32;
+
+    let top = lerp(lattice(x0, y0, seed), lattice(x0 + 1, y0, seed), tx);
+    let bottom = lerp(lattice(x0, y0 + 1, seed), lattice(x0 + 1, y0 + 1, seed), tx);
+
+    lerp(top, bottom, ty)
+}
+
+/// Sum of `octaves` layers of value noise, each at double the frequency and half
+/// the amplitude of the previous one, normalized back into `[0, 1]`.
+#[allow(dead_code)]
+pub fn fbm(p: Vector2<f32>, seed: u32, octaves: u32) -> f32 {
+    let mut sum = 0.0;
+    let mut total = 0.0;
+    let mut frequency = 1.0;
+    let mut amplitude = 1.0;
+
+    for octave in 0..octaves {
+        sum += value_noise_2d(p * frequency, seed.wrapping_add(octave)) * amplitude;
+        total += amplitude;
+
+        frequency *= 2.0;
+        amplitude *= 0.5;
+    }
+
+    if total == 0.0 {
+        0.0
+    } else {
+        sum / total
+    }
+}
+
+#[test]
+fn test_noise_deterministic() {
+    for i in 0..50 {
+        let p = Vector2::new(i as f32 * 0.37 - 5.0, i as f32 * 1.13 + 2.0);
+
+        assert_eq!(value_noise_2d(p, 7), value_noise_2d(p, 7));
+        assert_eq!(fbm(p, 7, 5), fbm(p, 7, 5));
+    }
+
+    let p = Vector2::new(0.5, 0.5);
+    assert!((0..16).any(|seed| value_noise_2d(p, seed) != value_noise_2d(p, 0)));
+}
+
+#[test]
+fn test_noise_range() {
+    for x in -40..40 {
+        for y in -40..40 {
+            let p = Vector2::new(x as f32 * 0.173, y as f32 * 0.291);
+
+            let value = value_noise_2d(p, 1234);
+            assert!((0.0..=1.0).contains(&value));
+
+            let value = fbm(p, 1234, 6);
+            assert!((0.0..=1.0).contains(&value));
+        }
+    }
+}
+
+#[test]
+fn test_noise_matches_lattice() {
+    assert_eq!(
+        value_noise_2d(Vector2::new(3.0, -2.0), 42),
+        lattice(3, -2, 42)
+    );
+}
@@ -0,0 +1,327 @@
+use std::f32::consts::PI;
+
+use crate::math::vectors::{Vector2, Vector3, Vector4};
+use crate::mesh::{Mesh, Vertex};
+
+// All shapes are centered on the origin and wound so that faces seen from
+// outside are front faces for the default FrontFace::Clockwise (clockwise
+// on screen after the viewport flips y).
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PrimitiveVertex {
+    pub position: Vector4<f32>,
+    pub normal: Vector3<f32>,
+    pub uv: Vector2<f32>,
+}
+
+impl PrimitiveVertex {
+    fn new(position: Vector3<f32>, normal: Vector3<f32>, uv: Vector2<f32>) -> Self {
+        Self {
+            position: position.into(),
+            normal,
+            uv,
+        }
+    }
+}
+
+impl Vertex for PrimitiveVertex {
+    fn position(&self) -> Vector4<f32> {
+        self.position
+    }
+
+    fn blend(vertices: (Self, Self, Self), coefs: Vector3<f32>) -> Self {
+        Self {
+            position: coefs.interpolate((
+                vertices.0.position,
+                vertices.1.position,
+                vertices.2.position,
+            )),
+            normal: coefs.interpolate((vertices.0.normal, vertices.1.normal, vertices.2.normal)),
+            uv: coefs.interpolate((vertices.0.uv, vertices.1.uv, vertices.2.uv)),
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub fn cube(size: f32) -> Mesh<PrimitiveVertex> {
+    let half = size / 2.0;
+
+    let x = Vector3::new(1.0, 0.0, 0.0);
+    let y = Vector3::new(0.0, 1.0, 0.0);
+    let z = Vector3::new(0.0, 0.0, 1.0);
+
+    // (normal, u, v) with u x v = normal.
+    let faces = [
+        (x, y, z),
+        (-x, z, y),
+        (y, z, x),
+        (-y, x, z),
+        (z, x, y),
+        (-z, y, x),
+    ];
+
+    let mut vertices = Vec::with_capacity(24);
+    let mut indices = Vec::with_capacity(12);
+
+    for (normal, u, v) in faces {
+        let base = vertices.len();
+        let center = normal * half;
+
+        for (su, sv, uv) in [
+            (-1.0, -1.0, Vector2::new(0.0, 1.0)),
+            (1.0, -1.0, Vector2::new(1.0, 1.0)),
+            (1.0, 1.0, Vector2::new(1.0, 0.0)),
+            (-1.0, 1.0, Vector2::new(0.0, 0.0)),
+        ] {
+            let position = center + u * (su * half) + v * (sv * half);
+            vertices.push(PrimitiveVertex::new(position, normal, uv));
+        }
+
+        indices.push((base, base + 2, base + 1));
+        indices.push((base, base + 3, base + 2));
+    }
+
+    Mesh::new(vertices, indices)
+}
+
+// Square in the xz plane facing +y, split into subdivisions x subdivisions quads.
+#[allow(dead_code)]
+pub fn plane(size: f32, subdivisions: u32) -> Mesh<PrimitiveVertex> {
+    let cells = subdivisions.max(1) as usize;
+    let stride = cells + 1;
+
+    let mut vertices = Vec::with_capacity(stride * stride);
+
+    for j in 0..stride {
+        for i in 0..stride {
+            let u = i as f32 / cells as f32;
+            let v = j as f32 / cells as f32;
+
+            vertices.push(PrimitiveVertex::new(
+                Vector3::new((u - 0.5) * size, 0.0, (v - 0.5) * size),
+                Vector3::new(0.0, 1.0, 0.0),
+                Vector2::new(u, v),
+            ));
+        }
+    }
+
+    let mut indices = Vec::with_capacity(2 * cells * cells);
+
+    for j in 0..cells {
+        for i in 0..cells {
+            let a = j * stride + i;
+            let b = a + 1;
+            let c = a + stride + 1;
+            let d = a + stride;
+
+            indices.push((a, b, c));
+            indices.push((a, c, d));
+        }
+    }
+
+    Mesh::new(vertices, indices)
+}
+
+// Latitude/longitude sphere; the seam column is duplicated so uvs wrap cleanly.
+#[allow(dead_code)]
+pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Mesh<PrimitiveVertex> {
+    let segments = segments.max(3) as usize;
+    let rings = rings.max(2) as usize;
+    let stride = segments + 1;
+
+    let mut vertices = Vec::with_capacity((rings + 1) * stride);
+
+    for ring in 0..=rings {
+        let v = ring as f32 / rings as f32;
+        let (sin_phi, cos_phi) = (v * PI).sin_cos();
+
+        for segment in 0..=segments {
+            let u = segment as f32 / segments as f32;
+            let (sin_theta, cos_theta) = (u * 2.0 * PI).sin_cos();
+
+            let normal = Vector3::new(sin_phi * cos_theta, cos_phi, sin_phi * sin_theta);
+            vertices.push(PrimitiveVertex::new(
+                normal * radius,
+                normal,
+                Vector2::new(u, v),
+            ));
+        }
+    }
+
+    let mut indices = Vec::with_capacity(segments * (2 * rings - 2));
+
+    for ring in 0..rings {
+        for segment in 0..segments {
+            let a = ring * stride + segment;
+            let b = a + 1;
+            let c = a + stride + 1;
+            let d = a + stride;
+
+            if ring != 0 {
+                indices.push((a, c, b));
+            }
+
+            if ring != rings - 1 {
+                indices.push((a, d, c));
+            }
+        }
+    }
+
+    Mesh::new(vertices, indices)
+}
+
+// Capped cylinder along y; side and caps have separate vertices for their normals.
+#[allow(dead_code)]
+pub fn cylinder(radius: f32, height: f32, segments: u32) -> Mesh<PrimitiveVertex> {
+    let segments = segments.max(3) as usize;
+    let half = height / 2.0;
+
+    let mut vertices = Vec::with_capacity(4 * segments + 6);
+    let mut indices = Vec::with_capacity(4 * segments);
+
+    let ring = |segment: usize| {
+        let u = segment as f32 / segments as f32;
+        let (sin, cos) = (u * 2.0 * PI).sin_cos();
+
+        (u, Vector3::new(cos, 0.0, sin))
+    };
+
+    for segment in 0..=segments {
+        let (u, normal) = ring(segment);
+        let rim = normal * radius;
+
+        vertices.push(PrimitiveVertex::new(
+            rim + Vector3::new(0.0, half, 0.0),
+            normal,
+            Vector2::new(u, 0.0),
+        ));
+        vertices.push(PrimitiveVertex::new(
+            rim - Vector3::new(0.0, half, 0.0),
+            normal,
+            Vector2::new(u, 1.0),
+        ));
+    }
+
+    for segment in 0..segments {
+        let top = 2 * segment;
+        let bottom = top + 1;
+
+        indices.push((top, bottom + 2, top + 2));
+        indices.push((top, bottom, bottom + 2));
+    }
+
+    for y in [half, -half] {
+        let normal = Vector3::new(0.0, y.signum(), 0.0);
+        let center = vertices.len();
+
+        vertices.push(PrimitiveVertex::new(
+            Vector3::new(0.0, y, 0.0),
+            normal,
+            Vector2::new(0.5, 0.5),
+        ));
+
+        for segment in 0..=segments {
+            let (_, direction) = ring(segment);
+
+            vertices.push(PrimitiveVertex::new(
+                direction * radius + Vector3::new(0.0, y, 0.0),
+                normal,
+                Vector2::new(0.5 + direction.x / 2.0, 0.5 + direction.z / 2.0),
+            ));
+        }
+
+        for segment in 0..segments {
+            let (a, b) = (center + 1 + segment, center + 2 + segment);
+
+            if y > 0.0 {
+                indices.push((center, a, b));
+            } else {
+                indices.push((center, b, a));
+            }
+        }
+    }
+
+    Mesh::new(vertices, indices)
+}
+
+#[cfg(test)]
+fn faces_outward(mesh: &Mesh<PrimitiveVertex>) -> bool {
+    mesh.triangles().all(|(a, b, c)| {
+        let a: Vector3<f32> = a.position.into();
+        let b: Vector3<f32> = b.position.into();
+        let c: Vector3<f32> = c.position.into();
+
+        let centroid = (a + b + c) / 3.0;
+
+        (b - a).cross(c - a).dot(centroid) < 0.0
+    })
+}
+
+#[test]
+fn test_cube() {
+    let mesh = cube(2.0);
+
+    assert_eq!(mesh.vertices.len(), 24);
+    assert_eq!(mesh.indices.len(), 12);
+    assert!(faces_outward(&mesh));
+
+    for vertex in &mesh.vertices {
+        let position: Vector3<f32> = vertex.position.into();
+
+        assert_eq!(position.dot(vertex.normal), 1.0);
+        assert_eq!(vertex.position.w, 1.0);
+    }
+}
+
+#[test]
+fn test_plane() {
+    let mesh = plane(4.0, 3);
+
+    assert_eq!(mesh.vertices.len(), 16);
+    assert_eq!(mesh.indices.len(), 18);
+
+    assert_eq!(
+        mesh.vertices[0].position,
+        Vector4::new(-2.0, 0.0, -2.0, 1.0)
+    );
+    assert_eq!(mesh.vertices[15].uv, Vector2::new(1.0, 1.0));
+
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    assert!(mesh.triangles().all(|(a, b, c)| {
+        let a: Vector3<f32> = a.position.into();
+        (Vector3::from(b.position) - a)
+            .cross(Vector3::from(c.position) - a)
+            .dot(up)
+            < 0.0
+    }));
+}
+
+#[test]
+fn test_uv_sphere() {
+    let mesh = uv_sphere(2.5, 12, 6);
+
+    assert_eq!(mesh.vertices.len(), 7 * 13);
+    assert_eq!(mesh.indices.len(), 12 * (2 * 6 - 2));
+    assert!(faces_outward(&mesh));
+
+    for vertex in &mesh.vertices {
+        let position: Vector3<f32> = vertex.position.into();
+
+        assert!((position.length() - 2.5).abs() < 1e-5);
+        assert!((vertex.normal.length() - 1.0).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_cylinder() {
+    let mesh = cylinder(1.0, 2.0, 8);
+
+    assert_eq!(mesh.vertices.len(), 2 * 9 + 2 * 10);
+    assert_eq!(mesh.indices.len(), 4 * 8);
+    assert!(faces_outward(&mesh));
+
+    assert!(mesh
+        .vertices
+        .iter()
+        .all(|vertex| vertex.position.y.abs() == 1.0 || vertex.position.y == 0.0));
+}
@@ -24,6 +24,25 @@ impl Rect2 {
     pub fn end(&self) -> Vector2<f32> {
         self.end
     }
+
+    #[allow(dead_code)]
+    pub fn inflate(&self, margin: f32) -> Option<Self> {
+        let margin = Vector2::new(margin, margin);
+
+        let start = self.start.min(self.end) - margin;
+        let end = self.start.max(self.end) + margin;
+
+        if end.x < start.x || end.y < start.y {
+            return None;
+        }
+
+        Self::new(start, end)
+    }
+
+    #[allow(dead_code)]
+    pub fn deflate(&self, margin: f32) -> Option<Self> {
+        self.inflate(-margin)
+    }
 }
 
 impl IntoIterator for Rect2 {
@@ -42,6 +61,21 @@ fn test_rect2() {
     let _rect2 = Rect2::new(Vector2::new(100.0, 100.0), Vector2::new(0.0, 0.0)).unwrap();
 }
 
+#[test]
+fn test_rect2_inflate_deflate() {
+    let rect = Rect2::new(Vector2::new(10.0, 10.0), Vector2::new(0.0, 0.0)).unwrap();
+
+    let inflated = rect.inflate(2.0).unwrap();
+    assert_eq!(inflated.start(), Vector2::new(-2.0, -2.0));
+    assert_eq!(inflated.end() - inflated.start(), Vector2::new(14.0, 14.0));
+
+    let deflated = rect.deflate(3.0).unwrap();
+    assert_eq!(deflated.end() - deflated.start(), Vector2::new(4.0, 4.0));
+
+    let small = Rect2::new(Vector2::new(0.0, 0.0), Vector2::new(4.0, 4.0)).unwrap();
+    assert!(small.deflate(3.0).is_none());
+}
+
 #[derive(Debug)]
 pub struct Rect2Iter {
     start: Vector2<i32>,
@@ -78,6 +112,27 @@ impl Rect2Iter {
             current: start,
         }
     }
+
+    pub fn clamp(mut self, size: Vector2<i32>) -> Self {
+        self.start = self.start.max(Vector2::new(0, 0));
+        self.end = self.end.min(size - Vector2::new(1, 1));
+        self.current = self.start;
+
+        if self.start.x > self.end.x {
+            self.current.y = self.end.y + 1;
+        }
+
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn skip_to_row(&mut self, y: i32) {
+        if self.start.x > self.end.x {
+            return;
+        }
+
+        self.current = Vector2::new(self.start.x, y.max(self.start.y).min(self.end.y + 1));
+    }
 }
 
 impl Iterator for Rect2Iter {
@@ -126,38 +181,231 @@ fn testing_iter_end() {
     assert_eq!(iter.next(), Some(Vector2::new(100, 100)));
 }
 
+#[test]
+fn testing_iter_skip_to_row() {
+    let mut iter =
+        Rect2Iter::new(Rect2::new(Vector2::new(3.0, 2.0), Vector2::new(10.0, 8.0)).unwrap());
+
+    iter.skip_to_row(5);
+    assert_eq!(iter.next(), Some(Vector2::new(3, 5)));
+    assert_eq!(iter.count(), 8 * 4 - 1);
+
+    let mut iter = _test_iter();
+    iter.skip_to_row(-10);
+    assert_eq!(iter.next(), Some(Vector2::new(0, 0)));
+
+    iter.skip_to_row(1000);
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn testing_iter_skip_to_row_clamped_out() {
+    let mut iter =
+        Rect2Iter::new(Rect2::new(Vector2::new(100.0, 0.0), Vector2::new(200.0, 10.0)).unwrap())
+            .clamp(Vector2::new(64, 32));
+
+    iter.skip_to_row(5);
+    assert_eq!(iter.next(), None);
+}
+
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DepthFunc {
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Always,
+    Never,
+}
+
+impl DepthFunc {
+    pub fn test<T: PartialOrd>(self, depth: T, stored: T) -> bool {
+        match self {
+            DepthFunc::Less => depth < stored,
+            DepthFunc::LessEqual => depth <= stored,
+            DepthFunc::Greater => depth > stored,
+            DepthFunc::GreaterEqual => depth >= stored,
+            DepthFunc::Always => true,
+            DepthFunc::Never => false,
+        }
+    }
+}
+
+#[test]
+fn test_depth_func() {
+    let stored = [0.25, 0.5, 0.75];
+    let depth = 0.5;
+
+    let passes = |func: DepthFunc| {
+        stored
+            .iter()
+            .map(|&stored| func.test(depth, stored))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(passes(DepthFunc::Less), [false, false, true]);
+    assert_eq!(passes(DepthFunc::LessEqual), [false, true, true]);
+    assert_eq!(passes(DepthFunc::Greater), [true, false, false]);
+    assert_eq!(passes(DepthFunc::GreaterEqual), [true, true, false]);
+    assert_eq!(passes(DepthFunc::Always), [true, true, true]);
+    assert_eq!(passes(DepthFunc::Never), [false, false, false]);
+}
+
+#[test]
+fn testing_iter_clamp() {
+    let size = Vector2::new(64, 32);
+
+    let positions: Vec<_> = Rect2Iter::new(
+        Rect2::new(Vector2::new(-50.0, 10.0), Vector2::new(100000.0, 20.0)).unwrap(),
+    )
+    .clamp(size)
+    .collect();
+
+    assert_eq!(positions.len(), 64 * 11);
+    assert_eq!(positions[0], Vector2::new(0, 10));
+    assert_eq!(positions[positions.len() - 1], Vector2::new(63, 20));
+
+    let outside =
+        Rect2Iter::new(Rect2::new(Vector2::new(100.0, 0.0), Vector2::new(200.0, 10.0)).unwrap())
+            .clamp(size);
+
+    assert_eq!(outside.count(), 0);
+}
+
 type Triplet = [Vector3<f32>; 3];
 type Triplet4 = [Vector4<f32>; 3];
 
+pub const NEAR_W_EPSILON: f32 = 1e-5;
+
+#[allow(dead_code)]
+pub const SUBPIXEL_BITS: u32 = 4;
+
+// Finer grids are below f32 precision at framebuffer coordinates anyway.
+pub const MAX_SUBPIXEL_BITS: u32 = 16;
+
+#[cfg(test)]
+thread_local! {
+    static BUILDS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+// Winding of front faces as seen on screen, with y pointing down.
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrontFace {
+    Clockwise,
+    CounterClockwise,
+}
+
+impl FrontFace {
+    fn sign(self) -> f32 {
+        match self {
+            FrontFace::Clockwise => 1.0,
+            FrontFace::CounterClockwise => -1.0,
+        }
+    }
+}
+
+// Degenerate also covers triangles whose bounding box is thinner than a
+// pixel; OffScreen is any vertex at or behind the camera plane.
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TriangleError {
+    Degenerate,
+    Backfacing,
+    OffScreen,
+}
+
 #[derive(Copy, Clone)]
 pub struct Triangle {
     vertices: Triplet,
     segments: Triplet,
     ws: Vector3<f32>,
     rect: Rect2,
+    front_face: FrontFace,
 }
 
 impl Triangle {
     pub fn new(vertices: Triplet4) -> Option<Self> {
+        Self::with_front_face(vertices, FrontFace::Clockwise)
+    }
+
+    pub fn with_front_face(vertices: Triplet4, front_face: FrontFace) -> Option<Self> {
+        let (vertices, ws) = Self::project(vertices).ok()?;
+
+        Self::build(vertices, ws, front_face).ok()
+    }
+
+    #[allow(dead_code)]
+    pub fn with_subpixel_snap(vertices: Triplet4, bits: u32) -> Option<Self> {
+        let (vertices, ws) = Self::project(vertices).ok()?;
+
+        Self::build(Self::snap(vertices, bits), ws, FrontFace::Clockwise).ok()
+    }
+
+    #[allow(dead_code)]
+    pub fn try_new(vertices: Triplet4) -> Result<Self, TriangleError> {
+        let (vertices, ws) = Self::project(vertices)?;
+
+        Self::check(&vertices, FrontFace::Clockwise)?;
+
+        Self::build(vertices, ws, FrontFace::Clockwise)
+    }
+
+    fn project(vertices: Triplet4) -> Result<(Triplet, Vector3<f32>), TriangleError> {
+        if vertices.iter().any(|vertex| vertex.w <= NEAR_W_EPSILON) {
+            return Err(TriangleError::OffScreen);
+        }
+
         let ws = Vector3::new(vertices[0].w, vertices[1].w, vertices[2].w);
 
         let vertices: [Vector3<f32>; 3] =
             [vertices[0].into(), vertices[1].into(), vertices[2].into()];
 
+        Ok((vertices, ws))
+    }
+
+    // Rounds x and y to a 1 / 2^bits pixel grid, like fixed-point hardware
+    // rasterizers; bits is clamped to MAX_SUBPIXEL_BITS.
+    fn snap(vertices: Triplet, bits: u32) -> Triplet {
+        let scale = (1u32 << bits.min(MAX_SUBPIXEL_BITS)) as f32;
+        let snap = |value: f32| (value * scale).round() / scale;
+
+        vertices.map(|vertex| Vector3::new(snap(vertex.x), snap(vertex.y), vertex.z))
+    }
+
+    fn build(
+        vertices: Triplet,
+        ws: Vector3<f32>,
+        front_face: FrontFace,
+    ) -> Result<Self, TriangleError> {
+        #[cfg(test)]
+        BUILDS.with(|builds| builds.set(builds.get() + 1));
+
         let segments = Self::segments(&vertices);
 
-        let rect = Self::get_rect(&vertices)?;
+        let rect = Self::get_rect(&vertices).ok_or(TriangleError::Degenerate)?;
 
-        if Self::check(&vertices, &segments) {
-            Some(Self {
-                vertices,
-                segments,
-                ws,
-                rect,
-            })
-        } else {
-            None
-        }
+        Self::check(&vertices, front_face)?;
+
+        Ok(Self {
+            vertices,
+            segments,
+            ws,
+            rect,
+            front_face,
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn signed_area(&self) -> f32 {
+        0.5 * Self::doubled_area(&self.vertices)
+    }
+
+    fn doubled_area(vertices: &Triplet) -> f32 {
+        (vertices[1] - vertices[0])
+            .cross(vertices[2] - vertices[0])
+            .z
     }
 
     fn segments(vertices: &Triplet) -> Triplet {
@@ -168,22 +416,16 @@ impl Triangle {
         [p1, p2, p3]
     }
 
-    fn check(vertices: &Triplet, segments: &Triplet) -> bool {
-        let middle = (vertices[0] + vertices[1] + vertices[2]) / 3.0;
+    fn check(vertices: &Triplet, front_face: FrontFace) -> Result<(), TriangleError> {
+        let area = Self::doubled_area(vertices) * front_face.sign();
 
-        let deltas = [
-            middle - vertices[0],
-            middle - vertices[1],
-            middle - vertices[2],
-        ];
-
-        for i in 0..3 {
-            if segments[i].cross(deltas[i]).z < 0.0 {
-                return false;
-            }
+        if area == 0.0 {
+            Err(TriangleError::Degenerate)
+        } else if area < 0.0 {
+            Err(TriangleError::Backfacing)
+        } else {
+            Ok(())
         }
-
-        true
     }
 
     fn get_rect(vertices: &Triplet) -> Option<Rect2> {
@@ -254,6 +496,152 @@ fn test_triangle_should_failed() {
     .unwrap();
 }
 
+#[test]
+fn test_triangle_try_new() {
+    let visible = [
+        Vector3::new(0.0, 0.0, 0.0).into(),
+        Vector3::new(100.0, 0.0, 0.0).into(),
+        Vector3::new(0.0, 100.0, 0.0).into(),
+    ];
+    let culled = [visible[0], visible[2], visible[1]];
+    let degenerate = [
+        Vector3::new(0.0, 0.0, 0.0).into(),
+        Vector3::new(50.0, 50.0, 0.0).into(),
+        Vector3::new(100.0, 100.0, 0.0).into(),
+    ];
+
+    BUILDS.with(|builds| builds.set(0));
+
+    for _ in 0..1000 {
+        assert_eq!(
+            Triangle::try_new(culled).err(),
+            Some(TriangleError::Backfacing)
+        );
+        assert_eq!(
+            Triangle::try_new(degenerate).err(),
+            Some(TriangleError::Degenerate)
+        );
+    }
+
+    assert_eq!(BUILDS.with(|builds| builds.get()), 0);
+
+    let expected: Vec<_> = Triangle::new(visible)
+        .unwrap()
+        .into_iter()
+        .map(|frag| (frag.position, frag.coefs))
+        .collect();
+    let actual: Vec<_> = Triangle::try_new(visible)
+        .unwrap()
+        .into_iter()
+        .map(|frag| (frag.position, frag.coefs))
+        .collect();
+
+    assert!(!expected.is_empty());
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn test_triangle_front_face() {
+    let vertices = [
+        Vector3::new(0.0, 0.0, 0.0).into(),
+        Vector3::new(100.0, 0.0, 0.0).into(),
+        Vector3::new(0.0, 100.0, 0.0).into(),
+    ];
+    let reversed = [vertices[0], vertices[2], vertices[1]];
+
+    assert!(Triangle::with_front_face(vertices, FrontFace::Clockwise).is_some());
+    assert!(Triangle::with_front_face(vertices, FrontFace::CounterClockwise).is_none());
+
+    assert!(Triangle::with_front_face(reversed, FrontFace::Clockwise).is_none());
+
+    let clockwise = Triangle::with_front_face(vertices, FrontFace::Clockwise)
+        .unwrap()
+        .into_iter()
+        .count();
+    let counter_clockwise = Triangle::with_front_face(reversed, FrontFace::CounterClockwise)
+        .unwrap()
+        .into_iter()
+        .count();
+
+    assert!(clockwise > 0);
+    assert_eq!(clockwise, counter_clockwise);
+}
+
+#[test]
+fn test_triangle_signed_area() {
+    let vertices = [
+        Vector3::new(0.0, 0.0, 0.0).into(),
+        Vector3::new(100.0, 0.0, 0.0).into(),
+        Vector3::new(0.0, 100.0, 0.0).into(),
+    ];
+    let reversed = [vertices[0], vertices[2], vertices[1]];
+
+    let front = Triangle::new(vertices).unwrap();
+    assert_eq!(front.signed_area(), 5000.0);
+
+    assert!(Triangle::new(reversed).is_none());
+
+    let back = Triangle::with_front_face(reversed, FrontFace::CounterClockwise).unwrap();
+    assert_eq!(back.signed_area(), -5000.0);
+}
+
+#[test]
+fn test_triangle_behind_camera() {
+    let vertices: Triplet4 = [
+        Vector3::new(0.0, 0.0, 0.0).into(),
+        Vector3::new(100.0, 0.0, 0.0).into(),
+        Vector3::new(0.0, 100.0, 0.0).into(),
+    ];
+
+    let flip = |vertex: Vector4<f32>| Vector4::new(-vertex.x, -vertex.y, -vertex.z, -vertex.w);
+
+    let mut one_behind = vertices;
+    one_behind[1] = flip(one_behind[1]);
+
+    let all_behind = [flip(vertices[0]), flip(vertices[1]), flip(vertices[2])];
+
+    let mut on_plane = vertices;
+    on_plane[2].w = 0.0;
+
+    assert!(Triangle::new(vertices).is_some());
+    assert!(Triangle::new(one_behind).is_none());
+    assert!(Triangle::new(all_behind).is_none());
+    assert!(Triangle::new(on_plane).is_none());
+    assert_eq!(
+        Triangle::try_new(all_behind).err(),
+        Some(TriangleError::OffScreen)
+    );
+}
+
+#[test]
+fn test_triangle_subpixel_snap() {
+    let vertices = |offset: f32| -> Triplet4 {
+        [
+            Vector3::new(0.3 + offset, 0.3, 0.0).into(),
+            Vector3::new(40.3 + offset, 0.3, 0.0).into(),
+            Vector3::new(0.3 + offset, 40.3, 0.0).into(),
+        ]
+    };
+
+    let coverage = |triangle: Triangle| {
+        triangle
+            .into_iter()
+            .map(|frag| frag.position)
+            .collect::<Vec<_>>()
+    };
+
+    let first = Triangle::with_subpixel_snap(vertices(0.0), SUBPIXEL_BITS).unwrap();
+    let second = Triangle::with_subpixel_snap(vertices(0.01), SUBPIXEL_BITS).unwrap();
+
+    assert_eq!(first.vertices[0].x, 0.3125);
+    assert_eq!(first.vertices, second.vertices);
+    assert_eq!(coverage(first), coverage(second));
+
+    let finest = Triangle::with_subpixel_snap(vertices(0.0), MAX_SUBPIXEL_BITS).unwrap();
+    let clamped = Triangle::with_subpixel_snap(vertices(0.0), 40).unwrap();
+    assert_eq!(finest.vertices, clamped.vertices);
+}
+
 struct LinearInterpolator {
     start: f32,
     dx: f32,
@@ -291,6 +679,9 @@ pub struct TriangleIter {
     start: Vector2<f32>,
     triangle: Triangle,
     zs: Vector3<f32>,
+    derivatives: bool,
+    coverage: bool,
+    edge_scale: Vector3<f32>,
 }
 
 impl TriangleIter {
@@ -305,23 +696,28 @@ impl TriangleIter {
             start - triangle.vertices[2].into(),
         ];
 
+        let sign = triangle.front_face.sign();
+
         let crosses = [
             LinearInterpolator::new(
-                triangle.segments[0].cross(vs[0].into()).z,
-                -triangle.segments[0].y,
-                triangle.segments[0].x,
+                triangle.segments[0].cross(vs[0].into()).z * sign,
+                -triangle.segments[0].y * sign,
+                triangle.segments[0].x * sign,
             ),
             LinearInterpolator::new(
-                triangle.segments[1].cross(vs[1].into()).z,
-                -triangle.segments[1].y,
-                triangle.segments[1].x,
+                triangle.segments[1].cross(vs[1].into()).z * sign,
+                -triangle.segments[1].y * sign,
+                triangle.segments[1].x * sign,
             ),
             LinearInterpolator::new(
-                triangle.segments[2].cross(vs[2].into()).z,
-                -triangle.segments[2].y,
-                triangle.segments[2].x,
+                triangle.segments[2].cross(vs[2].into()).z * sign,
+                -triangle.segments[2].y * sign,
+                triangle.segments[2].x * sign,
             ),
         ];
+
+        let inverse_length = |segment: Vector3<f32>| 1.0 / segment.x.hypot(segment.y);
+
         Self {
             rect_iter,
             crosses,
@@ -332,13 +728,57 @@ impl TriangleIter {
                 triangle.vertices[1].z,
                 triangle.vertices[2].z,
             ),
+            derivatives: false,
+            coverage: false,
+            edge_scale: Vector3::new(
+                inverse_length(triangle.segments[1]),
+                inverse_length(triangle.segments[2]),
+                inverse_length(triangle.segments[0]),
+            ),
         }
     }
+
+    pub fn clamped(mut self, size: Vector2<i32>) -> Self {
+        self.rect_iter = self.rect_iter.clamp(size);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_derivatives(mut self) -> Self {
+        self.derivatives = true;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_coverage(mut self) -> Self {
+        self.coverage = true;
+        self
+    }
+
+    fn edges(crosses: &[LinearInterpolator; 3], delta: Vector2<f32>) -> Vector3<f32> {
+        Vector3::new(
+            crosses[1].calc(delta.x, delta.y),
+            crosses[2].calc(delta.x, delta.y),
+            crosses[0].calc(delta.x, delta.y),
+        )
+    }
+
+    fn perspective(edges: Vector3<f32>, ws: Vector3<f32>) -> Vector3<f32> {
+        let coefs = Vector3::new(edges.x / ws.x, edges.y / ws.y, edges.z / ws.z);
+
+        coefs / (coefs.x + coefs.y + coefs.z)
+    }
 }
 
 pub struct Fragment {
     pub position: Vector3<f32>,
     pub coefs: Vector3<f32>,
+    // Clip-space w interpolated perspective-correctly, i.e. the view distance -z.
+    pub w: f32,
+    #[allow(dead_code)]
+    pub derivatives: Option<[Vector3<f32>; 2]>,
+    #[allow(dead_code)]
+    pub coverage: f32,
 }
 
 impl Iterator for TriangleIter {
@@ -349,26 +789,39 @@ impl Iterator for TriangleIter {
             let position = Vector2::<f32>::new(position.x as f32, position.y as f32);
             let delta = position - self.start;
 
-            let cof2 = self.crosses[0].calc(delta.x, delta.y);
-            let cof0 = self.crosses[1].calc(delta.x, delta.y);
-            let cof1 = self.crosses[2].calc(delta.x, delta.y);
+            let edges = Self::edges(&self.crosses, delta);
+
+            if edges.x > 0.0 && edges.y > 0.0 && edges.z > 0.0 {
+                let coefs = Self::perspective(edges, self.triangle.ws);
 
-            let mut coefs = Vector3::new(cof0, cof1, cof2);
+                let z = coefs.dot(self.zs);
 
-            if cof0 > 0.0 && cof1 > 0.0 && cof2 > 0.0 {
-                coefs.x /= self.triangle.ws.x;
-                coefs.y /= self.triangle.ws.y;
-                coefs.z /= self.triangle.ws.z;
+                let derivatives = self.derivatives.then(|| {
+                    let dx = Self::edges(&self.crosses, delta + Vector2::new(1.0, 0.0));
+                    let dy = Self::edges(&self.crosses, delta + Vector2::new(0.0, 1.0));
 
-                let sum = coefs.x + coefs.y + coefs.z;
+                    [
+                        Self::perspective(dx, self.triangle.ws) - coefs,
+                        Self::perspective(dy, self.triangle.ws) - coefs,
+                    ]
+                });
 
-                coefs = coefs / sum;
+                let coverage = if self.coverage {
+                    let distance = (edges.x * self.edge_scale.x)
+                        .min(edges.y * self.edge_scale.y)
+                        .min(edges.z * self.edge_scale.z);
 
-                let z = coefs * self.zs;
+                    (distance + 0.5).min(1.0)
+                } else {
+                    1.0
+                };
 
                 Some(Fragment {
                     position: Vector3::<f32>::new(position.x, position.y, z),
                     coefs,
+                    w: coefs.dot(self.triangle.ws),
+                    derivatives,
+                    coverage,
                 })
             } else {
                 None
@@ -376,3 +829,432 @@ impl Iterator for TriangleIter {
         })
     }
 }
+
+#[allow(dead_code)]
+pub fn mip_level(ddx: Vector2<f32>, ddy: Vector2<f32>, texture_size: Vector2<f32>) -> f32 {
+    let ddx = Vector2::new(ddx.x * texture_size.x, ddx.y * texture_size.y);
+    let ddy = Vector2::new(ddy.x * texture_size.x, ddy.y * texture_size.y);
+
+    let rho = ddx.dot(ddx).max(ddy.dot(ddy)).sqrt();
+
+    if rho <= 1.0 {
+        0.0
+    } else {
+        rho.log2()
+    }
+}
+
+#[test]
+fn test_fragment_derivatives() {
+    let triangle = Triangle::new([
+        Vector3::new(0.0, 0.0, 0.0).into(),
+        Vector3::new(100.0, 0.0, 0.0).into(),
+        Vector3::new(0.0, 100.0, 0.0).into(),
+    ])
+    .unwrap();
+
+    let uvs = (
+        Vector2::new(0.0, 0.0),
+        Vector2::new(1.0, 0.0),
+        Vector2::new(0.0, 1.0),
+    );
+
+    assert!(triangle.into_iter().all(|frag| frag.derivatives.is_none()));
+
+    let frag = triangle
+        .into_iter()
+        .with_derivatives()
+        .find(|frag| frag.position.x == 20.0 && frag.position.y == 30.0)
+        .unwrap();
+
+    let [dx, dy] = frag.derivatives.unwrap();
+    let ddx = dx.interpolate(uvs);
+    let ddy = dy.interpolate(uvs);
+
+    assert!((ddx.x - 0.01).abs() < 1e-5 && ddx.y.abs() < 1e-5);
+    assert!(ddy.x.abs() < 1e-5 && (ddy.y - 0.01).abs() < 1e-5);
+
+    assert!((mip_level(ddx, ddy, Vector2::new(400.0, 400.0)) - 2.0).abs() < 1e-3);
+    assert_eq!(mip_level(ddx, ddy, Vector2::new(50.0, 50.0)), 0.0);
+}
+
+#[test]
+fn test_fragment_w() {
+    let triangle = Triangle::new([
+        Vector4::new(0.0, 0.0, 0.0, 1.0),
+        Vector4::new(400.0, 0.0, 0.0, 4.0),
+        Vector4::new(0.0, 100.0, 0.0, 1.0),
+    ])
+    .unwrap();
+
+    let w_at = |x: f32, y: f32| {
+        triangle
+            .into_iter()
+            .find(|frag| frag.position.x == x && frag.position.y == y)
+            .unwrap()
+            .w
+    };
+
+    // 1/w is linear in screen space, so halfway towards w = 4 gives 1.6, not 2.5.
+    assert!((w_at(50.0, 1.0) - 1.6).abs() < 1e-4);
+    assert!((w_at(50.0, 20.0) - 1.6).abs() < 1e-4);
+    assert!(w_at(10.0, 10.0) < w_at(80.0, 10.0));
+}
+
+pub struct Line3 {
+    start: Vector3<f32>,
+    step: Vector3<f32>,
+    steps: i32,
+    current: i32,
+}
+
+#[allow(dead_code)]
+impl Line3 {
+    pub fn new(start: Vector4<f32>, end: Vector4<f32>) -> Self {
+        Self::between(start.into(), end.into())
+    }
+
+    // Liang-Barsky clip of the projected segment to the pixels of a
+    // `size` framebuffer, so the walk never leaves it.
+    pub fn clipped(start: Vector4<f32>, end: Vector4<f32>, size: Vector2<i32>) -> Option<Self> {
+        let start: Vector3<f32> = start.into();
+        let end: Vector3<f32> = end.into();
+
+        if ![start.x, start.y, end.x, end.y]
+            .iter()
+            .all(|value| value.is_finite())
+        {
+            return None;
+        }
+
+        let delta = end - start;
+        let max = Vector2::new((size.x - 1) as f32, (size.y - 1) as f32);
+
+        let (mut t0, mut t1) = (0.0f32, 1.0f32);
+
+        for (p, q) in [
+            (-delta.x, start.x),
+            (delta.x, max.x - start.x),
+            (-delta.y, start.y),
+            (delta.y, max.y - start.y),
+        ] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None;
+                }
+            } else if p < 0.0 {
+                t0 = t0.max(q / p);
+            } else {
+                t1 = t1.min(q / p);
+            }
+        }
+
+        if t0 > t1 {
+            return None;
+        }
+
+        Some(Self::between(start + delta * t0, start + delta * t1))
+    }
+
+    fn between(start: Vector3<f32>, end: Vector3<f32>) -> Self {
+        let delta = end - start;
+        let steps = delta.x.abs().max(delta.y.abs()).round() as i32;
+
+        let step = if steps == 0 {
+            Vector3::new(0.0, 0.0, 0.0)
+        } else {
+            delta / steps as f32
+        };
+
+        Self {
+            start,
+            step,
+            steps,
+            current: 0,
+        }
+    }
+}
+
+impl Iterator for Line3 {
+    type Item = (Vector2<i32>, f32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current > self.steps {
+            return None;
+        }
+
+        let point = self.start + self.step * self.current as f32;
+        self.current += 1;
+
+        Some((
+            Vector2::new(point.x.round() as i32, point.y.round() as i32),
+            point.z,
+        ))
+    }
+}
+
+#[test]
+fn test_line3_depth() {
+    let line: Vec<_> = Line3::new(
+        Vector3::new(0.0, 0.0, 0.2).into(),
+        Vector3::new(10.0, 4.0, 0.8).into(),
+    )
+    .collect();
+
+    assert_eq!(line.len(), 11);
+    assert_eq!(line[0].0, Vector2::new(0, 0));
+    assert_eq!(line[10].0, Vector2::new(10, 4));
+
+    let (position, depth) = line[5];
+
+    assert_eq!(position, Vector2::new(5, 2));
+    assert!((depth - 0.5).abs() < 1e-5);
+}
+
+#[test]
+fn test_line3_clipped() {
+    let size = Vector2::new(64, 32);
+
+    let line: Vec<_> = Line3::clipped(
+        Vector3::new(-1.0e7, 10.0, 0.0).into(),
+        Vector3::new(1.0e7, 10.0, 1.0).into(),
+        size,
+    )
+    .unwrap()
+    .collect();
+
+    assert_eq!(line.len(), 64);
+    assert_eq!(line[0].0, Vector2::new(0, 10));
+    assert_eq!(line[63].0, Vector2::new(63, 10));
+    assert!(line.iter().all(|(_, depth)| (depth - 0.5).abs() < 1e-3));
+
+    let inside: Vec<_> = Line3::clipped(
+        Vector3::new(0.0, 0.0, 0.2).into(),
+        Vector3::new(10.0, 4.0, 0.8).into(),
+        size,
+    )
+    .unwrap()
+    .collect();
+
+    assert_eq!(
+        inside,
+        Line3::new(
+            Vector3::new(0.0, 0.0, 0.2).into(),
+            Vector3::new(10.0, 4.0, 0.8).into()
+        )
+        .collect::<Vec<_>>()
+    );
+
+    assert!(Line3::clipped(
+        Vector3::new(100.0, 0.0, 0.0).into(),
+        Vector3::new(200.0, 10.0, 0.0).into(),
+        size,
+    )
+    .is_none());
+    assert!(Line3::clipped(
+        Vector3::new(0.0, 0.0, 0.0).into(),
+        Vector3::new(f32::INFINITY, 10.0, 0.0).into(),
+        size,
+    )
+    .is_none());
+}
+
+#[test]
+fn test_line3_point() {
+    let point = Vector3::new(3.0, 7.0, 0.4).into();
+    let line: Vec<_> = Line3::new(point, point).collect();
+
+    assert_eq!(line, vec![(Vector2::new(3, 7), 0.4)]);
+}
+
+// Round caps add a half-width disk at each endpoint; Square ends the line
+// flush with its endpoints.
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineCap {
+    Round,
+    Square,
+}
+
+pub struct WideLine3 {
+    rect_iter: Rect2Iter,
+    start: Vector3<f32>,
+    delta: Vector3<f32>,
+    radius: f32,
+    cap: LineCap,
+}
+
+#[allow(dead_code)]
+impl WideLine3 {
+    pub fn new(start: Vector4<f32>, end: Vector4<f32>, width: f32, cap: LineCap) -> Option<Self> {
+        let start: Vector3<f32> = start.into();
+        let end: Vector3<f32> = end.into();
+
+        let radius = width / 2.0;
+        let margin = Vector2::new(radius, radius);
+
+        let rect = Rect2::new(
+            Vector2::new(start.x.min(end.x), start.y.min(end.y)) - margin,
+            Vector2::new(start.x.max(end.x), start.y.max(end.y)) + margin,
+        )?;
+
+        Some(Self {
+            rect_iter: Rect2Iter::new(rect),
+            start,
+            delta: end - start,
+            radius,
+            cap,
+        })
+    }
+
+    pub fn clamped(mut self, size: Vector2<i32>) -> Self {
+        self.rect_iter = self.rect_iter.clamp(size);
+        self
+    }
+}
+
+impl Iterator for WideLine3 {
+    type Item = (Vector2<i32>, f32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let axis = Vector2::new(self.delta.x, self.delta.y);
+        let length_squared = axis.dot(axis);
+
+        self.rect_iter.find_map(|position| {
+            let offset = Vector2::new(position.x as f32, position.y as f32) - self.start.into();
+
+            let t = if length_squared > 0.0 {
+                offset.dot(axis) / length_squared
+            } else {
+                0.0
+            };
+
+            if self.cap == LineCap::Square && !(0.0..=1.0).contains(&t) {
+                return None;
+            }
+
+            let t = t.clamp(0.0, 1.0);
+            let closest = axis * t;
+
+            if offset.distance_squared(closest) > self.radius * self.radius {
+                return None;
+            }
+
+            Some((position, self.start.z + self.delta.z * t))
+        })
+    }
+}
+
+#[test]
+fn test_wide_line_caps() {
+    let start = Vector3::new(10.0, 10.0, 0.2).into();
+    let end = Vector3::new(20.0, 10.0, 0.6).into();
+
+    let round: Vec<_> = WideLine3::new(start, end, 3.0, LineCap::Round)
+        .unwrap()
+        .collect();
+
+    let mut cap: Vec<_> = round
+        .iter()
+        .filter(|(position, _)| position.x < 10)
+        .collect();
+    cap.sort_by_key(|(position, _)| position.y);
+
+    assert_eq!(
+        cap.iter()
+            .map(|(position, _)| *position)
+            .collect::<Vec<_>>(),
+        vec![Vector2::new(9, 9), Vector2::new(9, 10), Vector2::new(9, 11)]
+    );
+    assert!(cap.iter().all(|&&(_, depth)| depth == 0.2));
+
+    assert!(round
+        .iter()
+        .all(|(position, _)| (9..=11).contains(&position.y)));
+    assert_eq!(round.iter().filter(|(p, _)| p.x > 20).count(), 3);
+    assert_eq!(round.len(), 11 * 3 + 2 * 3);
+
+    let square: Vec<_> = WideLine3::new(start, end, 3.0, LineCap::Square)
+        .unwrap()
+        .collect();
+
+    assert_eq!(square.len(), 11 * 3);
+    assert!(square
+        .iter()
+        .all(|(position, _)| (10..=20).contains(&position.x)));
+
+    let (_, depth) = square
+        .iter()
+        .find(|(position, _)| *position == Vector2::new(15, 11))
+        .unwrap();
+    assert!((depth - 0.4).abs() < 1e-6);
+}
+
+#[test]
+fn test_fragment_coverage() {
+    let triangle = Triangle::new([
+        Vector3::new(0.0, 0.7, 0.0).into(),
+        Vector3::new(100.0, 0.7, 0.0).into(),
+        Vector3::new(0.0, 100.7, 0.0).into(),
+    ])
+    .unwrap();
+
+    assert!(triangle.into_iter().all(|frag| frag.coverage == 1.0));
+
+    let fragments: Vec<_> = triangle.into_iter().with_coverage().collect();
+
+    let at = |x: f32, y: f32| {
+        fragments
+            .iter()
+            .find(|frag| frag.position.x == x && frag.position.y == y)
+            .unwrap()
+            .coverage
+    };
+
+    assert_eq!(at(20.0, 20.0), 1.0);
+
+    let edge = at(20.0, 1.0);
+    assert!(edge > 0.0 && edge < 1.0);
+    assert!((edge - 0.8).abs() < 1e-4);
+
+    assert!(fragments
+        .iter()
+        .all(|frag| frag.coverage > 0.0 && frag.coverage <= 1.0));
+}
+
+#[test]
+fn test_triangle_guard_band() {
+    let size = Vector2::new(64, 64);
+
+    let triangle = Triangle::new([
+        Vector3::new(0.0, 0.0, 0.0).into(),
+        Vector3::new(100000.0, 0.0, 0.0).into(),
+        Vector3::new(0.0, 100.0, 0.0).into(),
+    ])
+    .unwrap();
+
+    let clamped: Vec<_> = triangle
+        .into_iter()
+        .clamped(size)
+        .map(|frag| (frag.position, frag.coefs))
+        .collect();
+
+    assert!(!clamped.is_empty());
+    assert!(clamped.iter().all(|(position, _)| {
+        (0.0..size.x as f32).contains(&position.x) && (0.0..size.y as f32).contains(&position.y)
+    }));
+
+    let expected: Vec<_> = Triangle::new([
+        Vector3::new(0.0, 0.0, 0.0).into(),
+        Vector3::new(100000.0, 0.0, 0.0).into(),
+        Vector3::new(0.0, 100.0, 0.0).into(),
+    ])
+    .unwrap()
+    .into_iter()
+    .take_while(|frag| frag.position.y < size.y as f32)
+    .filter(|frag| frag.position.x < size.x as f32)
+    .map(|frag| (frag.position, frag.coefs))
+    .collect();
+
+    assert_eq!(clamped, expected);
+}
@@ -0,0 +1,44 @@
+use crate::math::vectors::Vector3;
+
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Combine {
+    VertexColor,
+    Texture,
+    Modulate,
+}
+
+impl Combine {
+    pub fn apply(self, vertex: Vector3<f32>, texel: Vector3<f32>) -> Vector3<f32> {
+        match self {
+            Combine::VertexColor => vertex,
+            Combine::Texture => texel,
+            Combine::Modulate => {
+                Vector3::new(vertex.x * texel.x, vertex.y * texel.y, vertex.z * texel.z)
+            }
+        }
+    }
+}
+
+#[test]
+fn test_combine_modulate() {
+    let texel = Vector3::new(0.8, 0.4, 0.2);
+
+    assert_eq!(
+        Combine::Modulate.apply(Vector3::new(1.0, 1.0, 1.0), texel),
+        texel
+    );
+    assert_eq!(
+        Combine::Modulate.apply(Vector3::new(0.5, 0.5, 0.5), texel),
+        Vector3::new(0.4, 0.2, 0.1)
+    );
+}
+
+#[test]
+fn test_combine_select() {
+    let vertex = Vector3::new(1.0, 0.0, 0.0);
+    let texel = Vector3::new(0.0, 0.0, 1.0);
+
+    assert_eq!(Combine::VertexColor.apply(vertex, texel), vertex);
+    assert_eq!(Combine::Texture.apply(vertex, texel), texel);
+}